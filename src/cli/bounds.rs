@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::core::model::Model;
+
+use super::{load, var_name, CliError, Status};
+
+#[derive(Debug, Args)]
+pub struct BoundsArgs {
+    /// model file ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: PathBuf,
+}
+
+pub(super) fn run(args: &BoundsArgs) -> Result<Status, CliError> {
+    let mut model = load(&args.model)?;
+    Ok(print(&mut model))
+}
+
+/// propagate and print the interval of every variable, `Violated` if the model is infeasible
+pub(super) fn print(model: &mut Model) -> Status {
+    if let Err(e) = model.propagate() {
+        println!(
+            "infeasible, no value is left for {}",
+            var_name(model, e.var)
+        );
+        return Status::Violated;
+    }
+
+    for id in model.variables() {
+        let domain = model.bounds_of(id);
+        let lo = domain.lo.map_or("-inf".to_string(), |lo| lo.to_string());
+        let hi = domain.hi.map_or("inf".to_string(), |hi| hi.to_string());
+        println!("{}: [{}, {}]", var_name(model, id), lo, hi);
+    }
+    Status::Ok
+}
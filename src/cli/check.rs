@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::core::model::{Assignment, Model};
+use crate::core::table;
+
+use super::{load, CliError, Status};
+
+#[derive(Debug, Args)]
+#[command(group(
+    clap::ArgGroup::new("input")
+        .required(true)
+        .multiple(true)
+        .args(["values", "csv"])
+))]
+pub struct CheckArgs {
+    /// model file ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: PathBuf,
+    /// values of the variables by name or id, e.g. `x=5,y=7,#3=1`.
+    /// repeat to check several assignments
+    #[arg(long, value_name = "VALUES")]
+    pub values: Vec<String>,
+    /// csv file with one assignment per row, the header names the variables
+    #[arg(long, value_name = "FILE")]
+    pub csv: Option<PathBuf>,
+    /// print the summaries only
+    #[arg(long, short)]
+    pub quiet: bool,
+}
+
+/// `x=5,y=7` into an assignment
+pub fn parse_values(model: &Model, src: &str) -> Result<Assignment, CliError> {
+    let mut assignment = Assignment::new();
+    for pair in src.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let Some((var, value)) = pair.split_once('=') else {
+            return Err(CliError::Values(format!("`{}` is not `name=value`", pair)));
+        };
+        let (var, value) = (var.trim(), value.trim());
+        let id = model
+            .lookup(var)
+            .ok_or_else(|| CliError::Values(format!("unknown variable `{}`", var)))?;
+        let value = value
+            .parse()
+            .map_err(|_| CliError::Values(format!("`{}` is not an integer", value)))?;
+        if assignment.insert(id, value).is_some() {
+            return Err(CliError::Values(format!("`{}` is given twice", var)));
+        }
+    }
+    Ok(assignment)
+}
+
+pub(super) fn run(args: &CheckArgs) -> Result<Status, CliError> {
+    let model = load(&args.model)?;
+    // parse everything first, nothing is printed for invalid arguments
+    let assignments = args
+        .values
+        .iter()
+        .map(|v| parse_values(&model, v))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut ok = true;
+    for (report, src) in model.check_all(&assignments).iter().zip(args.values.iter()) {
+        ok &= report.is_ok();
+        if args.quiet {
+            println!("{}: {}", src, report.summary());
+        } else {
+            println!("{}: {}", src, report);
+        }
+    }
+
+    if let Some(csv) = &args.csv {
+        let validation = table::validate_path(&model, csv)?;
+        ok &= validation.is_ok();
+        if args.quiet {
+            println!("{}", validation.summary());
+        } else {
+            println!("{}", validation);
+        }
+    }
+
+    Ok(match ok {
+        true => Status::Ok,
+        false => Status::Violated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use clap::Parser;
+
+    use crate::core::file;
+
+    use super::super::{Cli, Command};
+    use super::*;
+
+    fn model() -> Model {
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        model.add_fixed(0, 5);
+        model.add_fixed(3, 1);
+        model
+    }
+
+    #[test]
+    fn values_name_variables_by_name_or_id() {
+        let model = model();
+        let values = parse_values(&model, " x = 5, #3=1,").unwrap();
+        assert_eq!(values, Assignment::from([(0, 5), (3, 1)]));
+
+        let error = |src| parse_values(&model, src).unwrap_err().to_string();
+        assert!(error("x").contains("`x` is not `name=value`"));
+        assert!(error("y=1").contains("unknown variable `y`"));
+        assert!(error("x=a").contains("`a` is not an integer"));
+        assert!(error("x=1,#0=2").contains("`#0` is given twice"));
+    }
+
+    #[test]
+    fn status_of_values_and_csv() {
+        let dir = std::env::temp_dir().join(format!("abstaction-check-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("model.toml");
+        file::write(&path, &model()).unwrap();
+        let csv = dir.join("rows.csv");
+        fs::write(&csv, "x,#3\n5,1\n5,2\n").unwrap();
+
+        let status = |args: &[&str]| {
+            let mut argv = vec!["abstaction", "check", path.to_str().unwrap()];
+            argv.extend_from_slice(args);
+            let Some(Command::Check(args)) = Cli::try_parse_from(argv).unwrap().command else {
+                panic!("not a check command");
+            };
+            run(&args).unwrap()
+        };
+        assert_eq!(status(&["--values", "x=5,#3=1"]), Status::Ok);
+        assert_eq!(
+            status(&["-q", "--values", "x=5,#3=1", "--values", "x=5"]),
+            Status::Violated
+        );
+        assert_eq!(status(&["--csv", csv.to_str().unwrap()]), Status::Violated);
+        // an assignment or a csv file is required
+        assert!(Cli::try_parse_from(["abstaction", "check", "model.toml"]).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use crate::core::file::{Format, ModelFile};
+use crate::core::lp;
+use crate::core::smtlib::{self, Logic};
+
+use super::{load, CliError, Status};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Target {
+    Smtlib,
+    Lp,
+    Mps,
+    Json,
+    Toml,
+    Yaml,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    /// model file ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: PathBuf,
+    /// format to write
+    #[arg(long)]
+    pub to: Target,
+    /// file to write, stdout if not given
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+pub(super) fn run(args: &ConvertArgs) -> Result<Status, CliError> {
+    let model = load(&args.model)?;
+
+    let out = match args.to {
+        Target::Smtlib => smtlib::write(&model, Logic::QfLia),
+        Target::Lp => lp::write_lp(&model)?,
+        Target::Mps => lp::write_mps(&model)?,
+        Target::Json => {
+            let mut json =
+                serde_json::to_string_pretty(&model).expect("model is always representable");
+            json.push('\n');
+            json
+        }
+        Target::Toml => ModelFile::from(&model).to_string(Format::Toml),
+        Target::Yaml => ModelFile::from(&model).to_string(Format::Yaml),
+    };
+    if !model.soft().is_empty() && matches!(args.to, Target::Smtlib | Target::Lp | Target::Mps) {
+        eprintln!("warning: soft constraints are written as hard ones");
+    }
+
+    match &args.output {
+        Some(path) => fs::write(path, out).map_err(|e| CliError::Io(path.clone(), e))?,
+        None => print!("{}", out),
+    }
+    Ok(Status::Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::file;
+    use crate::core::model::{Compare, Model};
+
+    use super::*;
+
+    #[test]
+    fn converted_models_load_back() {
+        let dir = std::env::temp_dir().join(format!("abstaction-convert-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("model.toml");
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        model.add_fixed(0, 4);
+        model.add_linear(0, 1, Some(2), Some(-1), Compare::LTE);
+        file::write(&path, &model).unwrap();
+
+        for (to, ext) in [
+            (Target::Lp, "lp"),
+            (Target::Mps, "mps"),
+            (Target::Smtlib, "smt2"),
+            (Target::Yaml, "yaml"),
+        ] {
+            let output = dir.join(format!("model.{}", ext));
+            let args = ConvertArgs {
+                model: path.clone(),
+                to,
+                output: Some(output.clone()),
+            };
+            assert_eq!(run(&args).unwrap(), Status::Ok);
+            let read = load(&output).unwrap();
+            assert_eq!(read.constraints(), model.constraints(), "{}", ext);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::core::file::LoadError;
+use crate::core::flatzinc::FznError;
+use crate::core::lp::LpError;
+use crate::core::smtlib::SmtError;
+use crate::core::statement::StatementError;
+use crate::core::table::TableError;
+use crate::lsp::LspError;
+
+#[derive(Debug)]
+pub enum CliError {
+    Io(PathBuf, std::io::Error),
+    /// the extension of the model path is none of the known formats
+    UnknownFormat(PathBuf),
+    Load(LoadError),
+    Lp(LpError),
+    Smt(SmtError),
+    Fzn(Vec<FznError>),
+    Table(TableError),
+    /// a `name=value` list that does not parse
+    Values(String),
+    Statement(StatementError),
+    /// a repl command with wrong arguments
+    Usage(String),
+    Lsp(LspError),
+}
+
+impl From<LspError> for CliError {
+    fn from(value: LspError) -> Self {
+        Self::Lsp(value)
+    }
+}
+
+impl From<StatementError> for CliError {
+    fn from(value: StatementError) -> Self {
+        Self::Statement(value)
+    }
+}
+
+impl From<LoadError> for CliError {
+    fn from(value: LoadError) -> Self {
+        Self::Load(value)
+    }
+}
+
+impl From<LpError> for CliError {
+    fn from(value: LpError) -> Self {
+        Self::Lp(value)
+    }
+}
+
+impl From<SmtError> for CliError {
+    fn from(value: SmtError) -> Self {
+        Self::Smt(value)
+    }
+}
+
+impl From<Vec<FznError>> for CliError {
+    fn from(value: Vec<FznError>) -> Self {
+        Self::Fzn(value)
+    }
+}
+
+impl From<TableError> for CliError {
+    fn from(value: TableError) -> Self {
+        Self::Table(value)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::UnknownFormat(path) => write!(
+                f,
+                "unknown model file format: {}, expected toml, yaml, lp, mps, smt2 or fzn",
+                path.display()
+            ),
+            CliError::Load(e) => write!(f, "{}", e),
+            CliError::Lp(e) => write!(f, "{}", e),
+            CliError::Smt(e) => write!(f, "{}", e),
+            CliError::Fzn(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+            CliError::Table(e) => write!(f, "{}", e),
+            CliError::Values(msg) => write!(f, "invalid values: {}", msg),
+            CliError::Statement(e) => write!(f, "invalid formula: {}", e),
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Lsp(e) => write!(f, "language server: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
@@ -0,0 +1,135 @@
+//! command line interface, the viewer is launched when no command is given
+//!
+//! every command exits with 0 when everything holds, 1 when a constraint is violated,
+//! 2 on errors ( unreadable files, invalid arguments ) and 3 when a search gave up before it
+//! found a solution or proved there is none.
+
+mod bounds;
+mod check;
+mod convert;
+mod errors;
+mod repl;
+mod solve;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+use crate::core::file::{self, Format};
+use crate::core::model::{Assignment, Model};
+use crate::core::{flatzinc, lp, smtlib};
+use crate::lsp;
+
+pub use self::bounds::BoundsArgs;
+pub use self::check::CheckArgs;
+pub use self::convert::{ConvertArgs, Target};
+pub use self::errors::CliError;
+pub use self::repl::ReplArgs;
+pub use self::solve::{OptimizeArgs, SolveArgs};
+
+#[derive(Debug, Parser)]
+#[command(name = "abstaction", version, about = "integer constraint models")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// check assignments against a model
+    Check(CheckArgs),
+    /// print an assignment satisfying the model
+    Solve(SolveArgs),
+    /// print an assignment satisfying the model with the best value of an expression
+    Optimize(OptimizeArgs),
+    /// print the interval of every variable implied by the constraints
+    Bounds(BoundsArgs),
+    /// write the model in another format
+    Convert(ConvertArgs),
+    /// edit and query a model interactively, statements are read line by line
+    Repl(ReplArgs),
+    /// language server for toml and yaml model files, over stdio
+    Lsp,
+}
+
+/// how a command ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// a constraint is violated or a value is missing
+    Violated,
+    Error,
+    /// the search limit was reached before an answer
+    Unknown,
+}
+
+impl From<Status> for ExitCode {
+    fn from(value: Status) -> Self {
+        match value {
+            Status::Ok => ExitCode::SUCCESS,
+            Status::Violated => ExitCode::from(1),
+            Status::Error => ExitCode::from(2),
+            Status::Unknown => ExitCode::from(3),
+        }
+    }
+}
+
+/// run a command, errors are printed to stderr
+pub fn run(command: Command) -> Status {
+    let result = match command {
+        Command::Check(args) => check::run(&args),
+        Command::Solve(args) => solve::solve(&args),
+        Command::Optimize(args) => solve::optimize(&args),
+        Command::Bounds(args) => bounds::run(&args),
+        Command::Convert(args) => convert::run(&args),
+        Command::Repl(args) => repl::run(&args),
+        Command::Lsp => lsp::run().map(|()| Status::Ok).map_err(CliError::from),
+    };
+    match result {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            Status::Error
+        }
+    }
+}
+
+/// read a model, the format is chosen by the extension
+/// ( toml, yaml, lp, mps, smt2 or fzn )
+pub fn load(path: &Path) -> Result<Model, CliError> {
+    if Format::from_path(path).is_some() {
+        return Ok(file::read(path)?);
+    }
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let src = || fs::read_to_string(path).map_err(|e| CliError::Io(PathBuf::from(path), e));
+
+    match ext {
+        "lp" => Ok(lp::read_lp(&src()?)?),
+        "mps" => Ok(lp::read_mps(&src()?)?),
+        "smt2" | "smt" => Ok(smtlib::read(&src()?)?),
+        "fzn" => Ok(flatzinc::read(&src()?)?),
+        _ => Err(CliError::UnknownFormat(path.into())),
+    }
+}
+
+/// `x`, or `#id` for unnamed variables
+pub fn var_name(model: &Model, id: usize) -> String {
+    match model.name(id) {
+        Some(name) => name.to_string(),
+        None => format!("#{}", id),
+    }
+}
+
+/// one `x = 5` line per variable, ordered by id
+pub fn print_assignment(model: &Model, assignment: &Assignment) {
+    let mut ids: Vec<_> = assignment.keys().copied().collect();
+    ids.sort();
+    for id in ids {
+        println!("{} = {}", var_name(model, id), assignment[&id]);
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::core::file::{self, Format};
+use crate::core::model::{Command, Constraint, ConstraintId, Model, Soft};
+
+use super::check::parse_values;
+use super::{bounds, load, solve, var_name, CliError, Status};
+
+#[derive(Debug, Args)]
+pub struct ReplArgs {
+    /// model file to start from ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: Option<PathBuf>,
+}
+
+const HELP: &str = "\
+a statement adds a constraint, e.g. `0 <= x < 20`, `y = 3` or `2 * x - 1 < y`.
+names the model does not know become new variables.
+
+  list [VAR]                constraints by id, or the ones of a variable
+  vars                      variables by id
+  name VAR NAME             rename a variable
+  remove ID                 remove a constraint, e.g. `remove c3`
+  soft ID [WEIGHT [PRIO]]   make a constraint soft, weight 1 and priority 0 by default
+  hard ID                   make a constraint required
+  check VALUES              check values, e.g. `check x=5, y=7`
+  bounds                    interval of every variable implied by the constraints
+  solve                     assignment satisfying the model
+  undo, redo                revert or repeat the last edit
+  save [FILE]               write the model to a toml or yaml file
+  load FILE                 read a model, `load!` discards unsaved edits
+  help                      this text
+  quit                      leave, `quit!` discards unsaved edits";
+
+/// what to do after a line
+enum Flow {
+    Continue,
+    Quit,
+}
+
+struct Session {
+    model: Model,
+    /// file of `save` without an argument
+    path: Option<PathBuf>,
+    /// no edit since the model was loaded or saved
+    saved: bool,
+}
+
+/// `c3` or `3`
+fn constraint_id(src: &str) -> Result<ConstraintId, CliError> {
+    src.strip_prefix('c')
+        .unwrap_or(src)
+        .parse()
+        .map(ConstraintId)
+        .map_err(|_| CliError::Usage(format!("`{}` is no constraint id, e.g. `c3`", src)))
+}
+
+impl Session {
+    fn var(&self, src: &str) -> Result<usize, CliError> {
+        self.model
+            .lookup(src)
+            .ok_or_else(|| CliError::Usage(format!("unknown variable `{}`", src)))
+    }
+
+    /// `c3: x < 20`, with the weight and priority of soft constraints
+    fn line(&self, cid: ConstraintId, constraint: &Constraint) -> String {
+        let mut line = format!("{}: {}", cid, self.model.describe(constraint));
+        if let Some(soft) = self.model.soft_of(cid) {
+            line.push_str(&format!(
+                " ( soft, weight {}, priority {} )",
+                soft.weight, soft.priority
+            ));
+        }
+        line
+    }
+
+    /// what an edit of the history did
+    fn command(&self, command: &Command) -> String {
+        match command {
+            Command::Add { cid, constraint } => format!("add {}", self.line(*cid, constraint)),
+            Command::Remove { cid, constraint } => {
+                format!("remove {}: {}", cid, self.model.describe(constraint))
+            }
+            Command::Replace { cid, new, .. } => {
+                format!("replace {}: {}", cid, self.model.describe(new))
+            }
+            Command::Rename { var, old, new } => format!(
+                "rename #{} from {} to {}",
+                var,
+                old.as_deref().unwrap_or("nothing"),
+                new.as_deref().unwrap_or("nothing")
+            ),
+            Command::Soften { cid, new, .. } => match new {
+                Some(_) => format!("soften {}", cid),
+                None => format!("harden {}", cid),
+            },
+        }
+    }
+
+    /// refuse to drop unsaved edits unless forced
+    fn discard(&self, force: bool) -> Result<(), CliError> {
+        match self.saved || force {
+            true => Ok(()),
+            false => Err(CliError::Usage(
+                "unsaved edits, `save` them or add `!` to discard them".to_string(),
+            )),
+        }
+    }
+
+    fn eval(&mut self, line: &str) -> Result<Flow, CliError> {
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args: Vec<_> = rest.split_whitespace().collect();
+        let usage = |text: &str| Err(CliError::Usage(format!("usage: {}", text)));
+
+        match (word, &args[..]) {
+            ("help", _) => println!("{}", HELP),
+            ("quit" | "exit", _) => {
+                self.discard(false)?;
+                return Ok(Flow::Quit);
+            }
+            ("quit!" | "exit!", _) => return Ok(Flow::Quit),
+            ("list", []) => {
+                for (cid, constraint) in self.model.constraints() {
+                    println!("{}", self.line(cid, &constraint));
+                }
+            }
+            ("list", [var]) => {
+                let var = self.var(var)?;
+                for (cid, constraint) in self.model.constraints() {
+                    if constraint.involves(var) {
+                        println!("{}", self.line(cid, &constraint));
+                    }
+                }
+            }
+            ("list", _) => return usage("list [VAR]"),
+            ("vars", _) => {
+                for id in self.model.variables() {
+                    match self.model.name(id) {
+                        Some(name) => println!("#{}: {}", id, name),
+                        None => println!("#{}", id),
+                    }
+                }
+            }
+            ("name", [var, name]) => {
+                let id = self.var(var)?;
+                let valid = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if !valid {
+                    return Err(CliError::Usage(format!("`{}` is no valid name", name)));
+                }
+                if self.model.id_of(name).is_some_and(|other| other != id) {
+                    return Err(CliError::Usage(format!("`{}` is already in use", name)));
+                }
+                self.model.set_name(id, *name);
+                self.saved = false;
+            }
+            ("name", _) => return usage("name VAR NAME"),
+            ("remove" | "rm", [cid]) => {
+                let cid = constraint_id(cid)?;
+                let constraint = self
+                    .model
+                    .remove(cid)
+                    .ok_or_else(|| CliError::Usage(format!("no constraint {}", cid)))?;
+                println!("removed {}: {}", cid, self.model.describe(&constraint));
+                self.saved = false;
+            }
+            ("remove" | "rm", _) => return usage("remove ID"),
+            ("soft", [cid, numbers @ ..]) if numbers.len() <= 2 => {
+                let cid = constraint_id(cid)?;
+                let mut soft = Soft::default();
+                if let Some(weight) = numbers.first() {
+                    soft.weight = weight
+                        .parse()
+                        .ok()
+                        .filter(|w| *w >= 0)
+                        .ok_or_else(|| CliError::Usage(format!("`{}` is no weight", weight)))?;
+                }
+                if let Some(priority) = numbers.get(1) {
+                    soft.priority = priority
+                        .parse()
+                        .map_err(|_| CliError::Usage(format!("`{}` is no priority", priority)))?;
+                }
+                if !self.model.set_soft(cid, soft) {
+                    return Err(CliError::Usage(format!("no constraint {}", cid)));
+                }
+                self.saved = false;
+            }
+            ("soft", _) => return usage("soft ID [WEIGHT [PRIORITY]]"),
+            ("hard", [cid]) => {
+                let cid = constraint_id(cid)?;
+                if self.model.get(cid).is_none() {
+                    return Err(CliError::Usage(format!("no constraint {}", cid)));
+                }
+                if self.model.set_hard(cid).is_some() {
+                    self.saved = false;
+                }
+            }
+            ("hard", _) => return usage("hard ID"),
+            ("check", _) => {
+                let assignment = parse_values(&self.model, rest)?;
+                println!("{}", self.model.check(&assignment));
+            }
+            ("bounds", []) => {
+                bounds::print(&mut self.model);
+            }
+            ("solve", []) => {
+                solve::print(&self.model);
+            }
+            ("undo", []) => match self.model.undo() {
+                Some(command) => {
+                    println!("undone: {}", self.command(&command));
+                    self.saved = false;
+                }
+                None => println!("nothing to undo"),
+            },
+            ("redo", []) => match self.model.redo() {
+                Some(command) => {
+                    println!("redone: {}", self.command(&command));
+                    self.saved = false;
+                }
+                None => println!("nothing to redo"),
+            },
+            ("save", []) => {
+                let path = self.path.clone().ok_or_else(|| {
+                    CliError::Usage("no file to save to, use `save FILE`".to_string())
+                })?;
+                file::write(&path, &self.model)?;
+                println!("saved {}", path.display());
+                self.saved = true;
+            }
+            ("save", [path]) => {
+                let path = PathBuf::from(path);
+                file::write(&path, &self.model)?;
+                println!("saved {}", path.display());
+                self.path = Some(path);
+                self.saved = true;
+            }
+            ("load" | "load!", [path]) => {
+                self.discard(word == "load!")?;
+                let path = PathBuf::from(path);
+                self.model = load(&path)?;
+                // models of other formats are saved with `save FILE`
+                self.path = Format::from_path(&path).map(|_| path);
+                self.saved = true;
+            }
+            ("save", _) => return usage("save [FILE]"),
+            ("load" | "load!", _) => return usage("load FILE"),
+            ("bounds" | "solve" | "undo" | "redo", _) => {
+                return usage(word);
+            }
+            _ => {
+                let cid = self.model.add_statement(line)?;
+                let constraint = self.model.get(cid).expect("the constraint was just added");
+                println!("{}", self.line(cid, &constraint));
+                self.saved = false;
+                if let Err(e) = self.model.propagated(&HashMap::new()) {
+                    println!(
+                        "the model is infeasible now, no value is left for {}",
+                        var_name(&self.model, e.var)
+                    );
+                }
+            }
+        }
+        Ok(Flow::Continue)
+    }
+}
+
+pub(super) fn run(args: &ReplArgs) -> Result<Status, CliError> {
+    let mut session = Session {
+        model: Model::new(),
+        path: None,
+        saved: true,
+    };
+    if let Some(path) = &args.model {
+        session.model = load(path)?;
+        session.path = Format::from_path(path).map(|_| path.clone());
+    }
+
+    // no prompt when the statements are piped in
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        println!("type `help` for the commands");
+    }
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            io::stdout()
+                .flush()
+                .map_err(|e| CliError::Io(PathBuf::from("<stdout>"), e))?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.map_err(|e| CliError::Io(PathBuf::from("<stdin>"), e))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match session.eval(line) {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Quit) => break,
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+    Ok(Status::Ok)
+}
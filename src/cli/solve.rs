@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::core::model::Model;
+use crate::core::solver::{Goal, LinearExpr, NoSolution, Solution};
+
+use super::{load, print_assignment, CliError, Status};
+
+#[derive(Debug, Args)]
+pub struct SolveArgs {
+    /// model file ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: PathBuf,
+}
+
+#[derive(Debug, Args)]
+#[command(group(
+    clap::ArgGroup::new("goal")
+        .required(true)
+        .args(["maximize", "minimize"])
+))]
+pub struct OptimizeArgs {
+    /// model file ( toml, yaml, lp, mps, smt2 or fzn )
+    pub model: PathBuf,
+    /// linear expression to maximize, e.g. `2 * x - y + 3`
+    #[arg(long, value_name = "EXPR", allow_hyphen_values = true)]
+    pub maximize: Option<String>,
+    /// linear expression to minimize
+    #[arg(long, value_name = "EXPR", allow_hyphen_values = true)]
+    pub minimize: Option<String>,
+}
+
+/// print the solution, `Violated` if there is none and `Unknown` if the search gave up
+fn report(model: &Model, solution: Result<Solution, NoSolution>, penalties: &[i64]) -> Status {
+    let solution = match solution {
+        Ok(solution) => solution,
+        Err(e) => {
+            println!("{}", e);
+            return match e {
+                NoSolution::Infeasible => Status::Violated,
+                NoSolution::LimitReached => Status::Unknown,
+            };
+        }
+    };
+    print_assignment(model, &solution.assignment);
+    if !model.soft().is_empty() {
+        let penalties: Vec<_> = penalties.iter().map(|p| p.to_string()).collect();
+        println!("penalties by priority: {}", penalties.join(", "));
+    }
+    if !solution.optimal {
+        println!("not proven optimal, the search limit was reached");
+    }
+    Status::Ok
+}
+
+pub(super) fn solve(args: &SolveArgs) -> Result<Status, CliError> {
+    Ok(print(&load(&args.model)?))
+}
+
+/// solve and print the solution, see [`report`]
+pub(super) fn print(model: &Model) -> Status {
+    let solution = model.solve();
+    let penalties = solution
+        .as_ref()
+        .map(|s| s.cost.clone())
+        .unwrap_or_default();
+    report(model, solution, &penalties)
+}
+
+pub(super) fn optimize(args: &OptimizeArgs) -> Result<Status, CliError> {
+    let model = load(&args.model)?;
+    let (src, goal) = match (&args.maximize, &args.minimize) {
+        (Some(src), _) => (src, Goal::Maximize),
+        (None, Some(src)) => (src, Goal::Minimize),
+        (None, None) => unreachable!("clap requires a goal"),
+    };
+    let expr = LinearExpr::parse(&model, src)?;
+
+    let solution = model.optimize(&expr, goal);
+    if solution.as_ref().is_ok_and(|s| !s.optimal) {
+        let bounds = model.propagated(&HashMap::new()).unwrap_or_default();
+        let unbounded = match goal {
+            Goal::Maximize => expr.range(&bounds).1.is_none(),
+            Goal::Minimize => expr.range(&bounds).0.is_none(),
+        };
+        if unbounded {
+            println!(
+                "{} may be unbounded, no bound follows from the constraints",
+                src.trim()
+            );
+        }
+    }
+    let penalties = match &solution {
+        Ok(s) => s.cost[1..].to_vec(),
+        Err(_) => Vec::new(),
+    };
+    if let Some(value) = solution
+        .as_ref()
+        .ok()
+        .and_then(|s| expr.eval(&s.assignment))
+    {
+        println!("{} = {}", src.trim(), value);
+    }
+    Ok(report(&model, solution, &penalties))
+}
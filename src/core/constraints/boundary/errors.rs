@@ -1,3 +1,5 @@
+use std::fmt::{Debug, Display};
+
 use super::super::MinMax;
 use super::Limit;
 
@@ -10,3 +12,30 @@ where
     InvalidLimits { top: Limit<T>, bottom: Limit<T> },
     CannotCmp { top: Limit<T>, bottom: Limit<T> },
 }
+
+impl<T> Display for BoundaryError<T>
+where
+    T: MinMax + PartialOrd + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundaryError::FixedPoint(point) => write!(
+                f,
+                "bottom and top limits meet at {}, use a fixed value instead",
+                point
+            ),
+            BoundaryError::InvalidLimits { top, bottom } => write!(
+                f,
+                "bottom limit {} is not below top limit {}",
+                bottom.point, top.point
+            ),
+            BoundaryError::CannotCmp { top, bottom } => write!(
+                f,
+                "bottom limit {} cannot be compared with top limit {}",
+                bottom.point, top.point
+            ),
+        }
+    }
+}
+
+impl<T> std::error::Error for BoundaryError<T> where T: MinMax + PartialOrd + Copy + Debug + Display {}
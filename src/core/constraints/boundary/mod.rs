@@ -3,9 +3,11 @@ mod errors;
 use super::{min_max::MinMax, Compare};
 pub use errors::BoundaryError;
 
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Limit<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -16,7 +18,20 @@ where
     pub equal: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// `20 (inclusive)` or `20 (exclusive)`
+impl<T> Display for Limit<T>
+where
+    T: MinMax + PartialOrd + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.equal {
+            true => write!(f, "{} (inclusive)", self.point),
+            false => write!(f, "{} (exclusive)", self.point),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Top<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -38,7 +53,7 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Bottom<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -78,15 +93,53 @@ where
     }
 }
 
+#[derive(Debug)]
 pub enum BoundaryCheckError<T>
 where
     T: MinMax + PartialOrd + Copy,
 {
-    TooLow { value: T, bottom: Bottom<T> },
-    TooHigh { value: T, top: Top<T> },
-    CannotCmp,
+    TooLow {
+        id: usize,
+        value: T,
+        bottom: Bottom<T>,
+    },
+    TooHigh {
+        id: usize,
+        value: T,
+        top: Top<T>,
+    },
+    CannotCmp {
+        id: usize,
+    },
 }
 
+impl<T> Display for BoundaryCheckError<T>
+where
+    T: MinMax + PartialOrd + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundaryCheckError::TooLow { id, value, bottom } => write!(
+                f,
+                "#{} = {} is below bottom limit {}",
+                id, value, bottom.limit
+            ),
+            BoundaryCheckError::TooHigh { id, value, top } => {
+                write!(f, "#{} = {} is above top limit {}", id, value, top.limit)
+            }
+            BoundaryCheckError::CannotCmp { id } => {
+                write!(f, "#{} cannot be compared with its limits", id)
+            }
+        }
+    }
+}
+
+impl<T> std::error::Error for BoundaryCheckError<T> where
+    T: MinMax + PartialOrd + Copy + Debug + Display
+{
+}
+
+#[derive(Debug)]
 pub enum BoundaryCheckResult<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -104,7 +157,7 @@ where
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Boundary<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -126,6 +179,10 @@ where
         }
     }
 
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     fn check_new_limits(bot: &Limit<T>, top: &Limit<T>) -> Result<(), BoundaryError<T>> {
         match bot.point.partial_cmp(&top.point) {
             Some(ord) => match ord {
@@ -157,8 +214,8 @@ where
         top: Option<Limit<T>>,
         bot: Option<Limit<T>>,
     ) -> Result<Self, BoundaryError<T>> {
-        if bot.is_some() && top.is_some() {
-            Self::check_new_limits(bot.as_ref().unwrap(), top.as_ref().unwrap())?;
+        if let (Some(bot), Some(top)) = (&bot, &top) {
+            Self::check_new_limits(bot, top)?;
         }
 
         Ok(Self {
@@ -176,16 +233,16 @@ where
     }
 
     pub fn set_top(&mut self, top: Limit<T>) -> Result<(), BoundaryError<T>> {
-        if self.bot.is_some() {
-            Self::check_new_limits(&self.bot.as_ref().unwrap().limit, &top)?;
+        if let Some(bot) = &self.bot {
+            Self::check_new_limits(&bot.limit, &top)?;
         }
         self.top = Some(top.into());
 
         Ok(())
     }
     pub fn set_bot(&mut self, bot: Limit<T>) -> Result<(), BoundaryError<T>> {
-        if self.top.is_some() {
-            Self::check_new_limits(&bot, &self.top.as_ref().unwrap().limit)?;
+        if let Some(top) = &self.top {
+            Self::check_new_limits(&bot, &top.limit)?;
         }
         self.bot = Some(bot.into());
 
@@ -208,13 +265,14 @@ where
                         //   value <= bot.limit.point
 
                         return BoundaryCheckError::TooLow {
+                            id: self.id,
                             value,
                             bottom: *bot,
                         }
                         .into();
                     }
                 }
-                None => return BoundaryCheckError::CannotCmp.into(),
+                None => return BoundaryCheckError::CannotCmp { id: self.id }.into(),
             };
         }
         if let Some(top) = &self.top {
@@ -231,13 +289,94 @@ where
                         // else (not top.limit.equal)
                         //   top.limit.point <= value
 
-                        return BoundaryCheckError::TooHigh { value, top: *top }.into();
+                        return BoundaryCheckError::TooHigh {
+                            id: self.id,
+                            value,
+                            top: *top,
+                        }
+                        .into();
                     }
                 }
-                None => return BoundaryCheckError::CannotCmp.into(),
+                None => return BoundaryCheckError::CannotCmp { id: self.id }.into(),
             };
         }
 
         BoundaryCheckResult::Ok
     }
 }
+
+impl Boundary<i64> {
+    /// margin of `value` to the nearest limit, negative by how far it is outside
+    pub fn slack(&self, value: i64) -> i64 {
+        let value = value as i128;
+        let above = self
+            .bot
+            .map(|bot| value - bot.limit.point as i128 - i128::from(!bot.limit.equal));
+        let below = self
+            .top
+            .map(|top| top.limit.point as i128 - value - i128::from(!top.limit.equal));
+        let slack = match (above, below) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(s), None) | (None, Some(s)) => s,
+            (None, None) => i64::MAX as i128,
+        };
+        slack.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+impl BoundaryCheckError<i64> {
+    /// how far the value is from the violated limit
+    pub fn distance(&self) -> i64 {
+        match self {
+            BoundaryCheckError::TooLow { value, bottom, .. } => bottom
+                .limit
+                .point
+                .saturating_add(i64::from(!bottom.limit.equal))
+                .saturating_sub(*value),
+            BoundaryCheckError::TooHigh { value, top, .. } => value
+                .saturating_sub(top.limit.point)
+                .saturating_add(i64::from(!top.limit.equal)),
+            BoundaryCheckError::CannotCmp { .. } => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(point: i64, equal: bool) -> Limit<i64> {
+        Limit { point, equal }
+    }
+
+    #[test]
+    fn errors_name_the_limits() {
+        let err = Boundary::create(0, Some(limit(3, true)), Some(limit(3, false))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "bottom and top limits meet at 3, use a fixed value instead"
+        );
+        let err = Boundary::create(0, Some(limit(3, false)), Some(limit(5, true))).unwrap_err();
+        assert_eq!(err.to_string(), "bottom limit 5 is not below top limit 3");
+        assert_eq!(limit(5, true).to_string(), "5 (inclusive)");
+        assert_eq!(limit(3, false).to_string(), "3 (exclusive)");
+    }
+
+    #[test]
+    fn check_errors_name_the_variable() {
+        let boundary = Boundary::create(2, Some(limit(10, false)), Some(limit(0, true))).unwrap();
+        assert!(matches!(boundary.is_in(0), BoundaryCheckResult::Ok));
+
+        let BoundaryCheckResult::Err(err) = boundary.is_in(-1) else {
+            panic!("-1 is below 0");
+        };
+        assert_eq!(
+            err.to_string(),
+            "#2 = -1 is below bottom limit 0 (inclusive)"
+        );
+        let BoundaryCheckResult::Err(err) = boundary.is_in(10) else {
+            panic!("10 is not below 10");
+        };
+        assert_eq!(err.to_string(), "#2 = 10 is above top limit 10 (exclusive)");
+    }
+}
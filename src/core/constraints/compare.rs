@@ -1,20 +1,50 @@
 use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Compare {
     /// Less then ( < )
+    #[serde(rename = "<")]
     LT,
     /// Less or equal ( <= )
+    #[serde(rename = "<=")]
     LTE,
     /// Greater then ( > )
+    #[serde(rename = ">")]
     GT,
     /// Greater or equal ( >= )
+    #[serde(rename = ">=")]
     GTE,
     /// Equal ( = )
+    #[serde(rename = "=")]
     EQ,
 }
 
 impl Compare {
+    /// symbol used in model files ( `<`, `<=`, `>`, `>=`, `=` )
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Compare::LT => "<",
+            Compare::LTE => "<=",
+            Compare::GT => ">",
+            Compare::GTE => ">=",
+            Compare::EQ => "=",
+        }
+    }
+
+    /// parse either the symbol or the variant name ( `<=` or `LTE` )
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "<" | "LT" => Some(Compare::LT),
+            "<=" | "LTE" => Some(Compare::LTE),
+            ">" | "GT" => Some(Compare::GT),
+            ">=" | "GTE" => Some(Compare::GTE),
+            "=" | "==" | "EQ" => Some(Compare::EQ),
+            _ => None,
+        }
+    }
+
     pub fn is_in(&self, cmp: Ordering) -> bool {
         match cmp {
             Ordering::Less => match self {
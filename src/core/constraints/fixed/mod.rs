@@ -1,14 +1,33 @@
+use std::fmt::{Debug, Display};
+
 /// represent id have to equal to value
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Fixed<V: PartialEq + Copy> {
     pub id: usize,
     pub value: V,
 }
 
+#[derive(Debug)]
 pub enum FixedCheckError<V: PartialEq + Copy> {
     NotEqual { fixed: Fixed<V>, value: V },
 }
+
+impl<V: PartialEq + Copy + Display> Display for FixedCheckError<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FixedCheckError::NotEqual { fixed, value } => write!(
+                f,
+                "#{} = {} is not the fixed value {}",
+                fixed.id, value, fixed.value
+            ),
+        }
+    }
+}
+
+impl<V: PartialEq + Copy + Debug + Display> std::error::Error for FixedCheckError<V> {}
+
+#[derive(Debug)]
 pub enum FixedCheckResult<V: PartialEq + Copy> {
     Ok,
     Err(FixedCheckError<V>),
@@ -29,3 +48,19 @@ impl<V: PartialEq + Copy> Fixed<V> {
         }
     }
 }
+
+impl Fixed<i64> {
+    /// negative distance of `value` to the fixed value, 0 if equal
+    pub fn slack(&self, value: i64) -> i64 {
+        -(value.abs_diff(self.value).min(i64::MAX as u64) as i64)
+    }
+}
+
+impl FixedCheckError<i64> {
+    /// how far the value is from the fixed one
+    pub fn distance(&self) -> i64 {
+        match self {
+            FixedCheckError::NotEqual { fixed, value } => -fixed.slack(*value),
+        }
+    }
+}
@@ -4,6 +4,7 @@ mod fixed;
 mod min_max;
 mod relation;
 
+use std::fmt::{Debug, Display};
 use std::ops::{Add, Mul};
 
 use self::boundary::BoundaryCheckError;
@@ -16,7 +17,7 @@ use self::relation::{LinearCheckError, LinearCheckResult};
 
 pub use compare::Compare;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SingleConstrain<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -25,7 +26,7 @@ where
     Fixed(Fixed<T>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DoubleConstrain<M, O>
 where
     M: Mul + Copy,
@@ -34,6 +35,7 @@ where
     LinearRelation(Linear<M, O>),
 }
 
+#[derive(Debug)]
 pub enum SingleConstrainCheckError<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -42,6 +44,24 @@ where
     FixedErr(FixedCheckError<T>),
 }
 
+impl<T> Display for SingleConstrainCheckError<T>
+where
+    T: MinMax + PartialOrd + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SingleConstrainCheckError::BoundaryErr(e) => write!(f, "{}", e),
+            SingleConstrainCheckError::FixedErr(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<T> std::error::Error for SingleConstrainCheckError<T> where
+    T: MinMax + PartialOrd + Copy + Debug + Display
+{
+}
+
+#[derive(Debug)]
 pub enum SingleConstrainCheckResult<T>
 where
     T: MinMax + PartialOrd + Copy,
@@ -87,6 +107,14 @@ impl<T> SingleConstrain<T>
 where
     T: MinMax + PartialOrd + Copy,
 {
+    /// id of the constrained variable
+    pub fn id(&self) -> usize {
+        match self {
+            SingleConstrain::Boundary(b) => b.id(),
+            SingleConstrain::Fixed(f) => f.id,
+        }
+    }
+
     pub fn check(&self, value: &T) -> SingleConstrainCheckResult<T> {
         match self {
             SingleConstrain::Boundary(b) => b.is_in(*value).into(),
@@ -95,6 +123,7 @@ where
     }
 }
 
+#[derive(Debug)]
 pub enum DoubleConstrainCheckError<T, M, O>
 where
     T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -104,6 +133,27 @@ where
     Linear(LinearCheckError<T, M, O>),
 }
 
+impl<T, M, O> Display for DoubleConstrainCheckError<T, M, O>
+where
+    T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd + Display,
+    M: Mul + Copy + Display,
+    O: Add + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DoubleConstrainCheckError::Linear(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<T, M, O> std::error::Error for DoubleConstrainCheckError<T, M, O>
+where
+    T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd + Debug + Display,
+    M: Mul + Copy + Debug + Display,
+    O: Add + Copy + Debug + Display,
+{
+}
+
 impl<T, M, O> From<LinearCheckError<T, M, O>> for DoubleConstrainCheckError<T, M, O>
 where
     T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -115,6 +165,7 @@ where
     }
 }
 
+#[derive(Debug)]
 pub enum DoubleConstrainCheckResult<T, M, O>
 where
     T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -144,6 +195,13 @@ where
     M: Mul + Copy,
     O: Add + Copy,
 {
+    /// ids of the ( left, right ) variables
+    pub fn ids(&self) -> (usize, usize) {
+        match self {
+            DoubleConstrain::LinearRelation(l) => (l.left(), l.right()),
+        }
+    }
+
     pub fn check<T>(&self, left: &T, right: &T) -> DoubleConstrainCheckResult<T, M, O>
     where
         T: MinMax + Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -153,3 +211,31 @@ where
         }
     }
 }
+
+impl DoubleConstrain<i64, i64> {
+    /// [`DoubleConstrain::check`] without overflowing, see [`Linear::is_in_wide`]
+    pub fn check_wide(&self, left: i64, right: i64) -> DoubleConstrainCheckResult<i64, i64, i64> {
+        match self {
+            DoubleConstrain::LinearRelation(l) => l.is_in_wide(left, right).into(),
+        }
+    }
+}
+
+impl SingleConstrainCheckError<i64> {
+    /// how far the value is from satisfying the constraint
+    pub fn distance(&self) -> i64 {
+        match self {
+            SingleConstrainCheckError::BoundaryErr(e) => e.distance(),
+            SingleConstrainCheckError::FixedErr(e) => e.distance(),
+        }
+    }
+}
+
+impl DoubleConstrainCheckError<i64, i64, i64> {
+    /// how far the values are from satisfying the constraint
+    pub fn distance(&self) -> i64 {
+        match self {
+            DoubleConstrainCheckError::Linear(e) => e.distance(),
+        }
+    }
+}
@@ -1,10 +1,11 @@
+use std::fmt::{Debug, Display};
 use std::ops::{Add, Mul};
 
 use super::Compare;
 
 /// linear equation relation
 /// ( left * mul + offset ) `cmp` right
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Linear<M, O>
 where
     M: Mul + Copy,
@@ -22,6 +23,40 @@ where
     off: Option<O>,
 }
 
+/// `#0 * 2 + 1 < #1`
+impl<M, O> Display for Linear<M, O>
+where
+    M: Mul + Copy + Display,
+    O: Add + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.formula(|id| format!("#{}", id)))
+    }
+}
+
+impl<M, O> Linear<M, O>
+where
+    M: Mul + Copy + Display,
+    O: Add + Copy + Display,
+{
+    /// the relation with every variable written by `var`, `Display` writes them as `#id`
+    pub fn formula(&self, var: impl Fn(usize) -> String) -> String {
+        let mut out = var(self.left);
+        if let Some(mul) = &self.mul {
+            out.push_str(&format!(" * {}", mul));
+        }
+        if let Some(off) = &self.off {
+            let off = off.to_string();
+            match off.strip_prefix('-') {
+                Some(abs) => out.push_str(&format!(" - {}", abs)),
+                None => out.push_str(&format!(" + {}", off)),
+            }
+        }
+        format!("{} {} {}", out, self.cmp.symbol(), var(self.right))
+    }
+}
+
+#[derive(Debug)]
 pub enum LinearCheckError<T, M, O>
 where
     T: Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -44,6 +79,45 @@ where
     },
 }
 
+impl<T, M, O> Display for LinearCheckError<T, M, O>
+where
+    T: Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd + Display,
+    M: Mul + Copy + Display,
+    O: Add + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinearCheckError::NotIn {
+                formula,
+                left,
+                right,
+            } => write!(
+                f,
+                "#{} = {}, #{} = {} violate {}",
+                formula.left, left, formula.right, right, formula
+            ),
+            LinearCheckError::CannotCompare {
+                formula,
+                left,
+                right,
+            } => write!(
+                f,
+                "#{} = {}, #{} = {} cannot be compared for {}",
+                formula.left, left, formula.right, right, formula
+            ),
+        }
+    }
+}
+
+impl<T, M, O> std::error::Error for LinearCheckError<T, M, O>
+where
+    T: Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd + Debug + Display,
+    M: Mul + Copy + Debug + Display,
+    O: Add + Copy + Debug + Display,
+{
+}
+
+#[derive(Debug)]
 pub enum LinearCheckResult<T, M, O>
 where
     M: Mul + Copy,
@@ -80,6 +154,26 @@ where
         }
     }
 
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    pub fn right(&self) -> usize {
+        self.right
+    }
+
+    pub fn cmp(&self) -> Compare {
+        self.cmp
+    }
+
+    pub fn mul(&self) -> Option<M> {
+        self.mul
+    }
+
+    pub fn off(&self) -> Option<O> {
+        self.off
+    }
+
     pub fn is_in<T>(&self, left: &T, right: &T) -> LinearCheckResult<T, M, O>
     where
         T: Copy + Mul<M, Output = T> + Add<O, Output = T> + PartialOrd,
@@ -94,6 +188,8 @@ where
 
         if let Some(cmp_result) = left_one.partial_cmp(right) {
             if self.cmp.is_in(cmp_result) {
+                LinearCheckResult::Ok
+            } else {
                 // the point is out of range
                 LinearCheckError::NotIn {
                     formula: *self,
@@ -101,8 +197,6 @@ where
                     right: *right,
                 }
                 .into()
-            } else {
-                LinearCheckResult::Ok
             }
         } else {
             LinearCheckError::CannotCompare {
@@ -114,3 +208,111 @@ where
         }
     }
 }
+
+impl Linear<i64, i64> {
+    /// [`Linear::is_in`] decided on the [`Linear::slack`], `left * mul + off` may leave the
+    /// range of an i64
+    pub fn is_in_wide(&self, left: i64, right: i64) -> LinearCheckResult<i64, i64, i64> {
+        if self.slack(left, right) >= 0 {
+            LinearCheckResult::Ok
+        } else {
+            LinearCheckError::NotIn {
+                formula: *self,
+                left,
+                right,
+            }
+            .into()
+        }
+    }
+
+    /// margin by which the relation holds in units of `right`, negative by how far it is
+    /// violated
+    pub fn slack(&self, left: i64, right: i64) -> i64 {
+        let lhs = left as i128 * self.mul.unwrap_or(1) as i128 + self.off.unwrap_or(0) as i128;
+        let diff = right as i128 - lhs;
+        let slack = match self.cmp {
+            Compare::LTE => diff,
+            Compare::LT => diff - 1,
+            Compare::GTE => -diff,
+            Compare::GT => -diff - 1,
+            Compare::EQ => -diff.abs(),
+        };
+        slack.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+impl LinearCheckError<i64, i64, i64> {
+    /// how far the values are from satisfying the relation
+    pub fn distance(&self) -> i64 {
+        match self {
+            LinearCheckError::NotIn {
+                formula,
+                left,
+                right,
+            } => formula.slack(*left, *right).saturating_neg().max(0),
+            LinearCheckError::CannotCompare { .. } => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holds(linear: Linear<i64, i64>, left: i64, right: i64) -> bool {
+        match linear.is_in(&left, &right) {
+            LinearCheckResult::Ok => true,
+            LinearCheckResult::Err(LinearCheckError::NotIn { .. }) => false,
+            LinearCheckResult::Err(LinearCheckError::CannotCompare { .. }) => {
+                panic!("integers always compare")
+            }
+        }
+    }
+
+    #[test]
+    fn is_in_holds_when_the_formula_does() {
+        // 2 * left + 1 <= right
+        let linear = Linear::new(0, 1, Some(2), Some(1), Compare::LTE);
+        assert!(holds(linear, 3, 7));
+        assert!(holds(linear, 3, 8));
+        assert!(!holds(linear, 4, 8));
+    }
+
+    #[test]
+    fn is_in_every_compare() {
+        let cases = [
+            (Compare::LT, [false, false, true]),
+            (Compare::LTE, [false, true, true]),
+            (Compare::GT, [true, false, false]),
+            (Compare::GTE, [true, true, false]),
+            (Compare::EQ, [false, true, false]),
+        ];
+        for (cmp, expected) in cases {
+            // left + 1 compared to 4, with left of 4, 3 and 2
+            let linear = Linear::new(0, 1, None, Some(1), cmp);
+            let got = [4, 3, 2].map(|left| holds(linear, left, 4));
+            assert_eq!(got, expected, "{:?}", cmp);
+        }
+    }
+
+    #[test]
+    fn is_in_wide_does_not_overflow() {
+        let linear = Linear::new(0, 1, Some(2), None, Compare::GT);
+        assert!(matches!(
+            linear.is_in_wide(9_000_000_000_000_000_000, 1),
+            LinearCheckResult::Ok
+        ));
+        assert!(matches!(
+            linear.is_in_wide(-9_000_000_000_000_000_000, 1),
+            LinearCheckResult::Err(LinearCheckError::NotIn { .. })
+        ));
+    }
+
+    #[test]
+    fn display_and_formula_agree() {
+        let linear: Linear<i64, i64> = Linear::new(0, 1, Some(2), Some(-3), Compare::LT);
+        assert_eq!(linear.to_string(), "#0 * 2 - 3 < #1");
+        let names = ["x", "y"];
+        assert_eq!(linear.formula(|id| names[id].to_string()), "x * 2 - 3 < y");
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use yaml_rust2::scanner::Marker;
+
+use super::super::constraints::BoundaryError;
+
+/// 1-indexed location in a model file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+impl Position {
+    /// position of the byte `offset` in `src`
+    pub fn from_offset(src: &str, offset: usize) -> Self {
+        let before = &src[..offset.min(src.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = match before.rfind('\n') {
+            Some(nl) => before[nl + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+
+        Self { line, column }
+    }
+
+    pub(crate) fn from_marker(marker: &Marker) -> Self {
+        Self {
+            line: marker.line(),
+            column: marker.col() + 1,
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum FileErrorKind {
+    /// the source is not valid toml / yaml
+    Syntax(String),
+    UnknownKey {
+        key: String,
+        expected: &'static [&'static str],
+    },
+    MissingKey(&'static str),
+    InvalidType {
+        key: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    InvalidValue {
+        key: String,
+        value: String,
+    },
+    /// a constraint refers to a name no `variable` declares
+    UnknownVariable(String),
+    DuplicateVariable(usize),
+    DuplicateName(String),
+    /// two constraints with the same `cid`
+    DuplicateConstraint(usize),
+    /// crossed or touching limits of a boundary
+    Boundary(BoundaryError<i64>),
+    /// `left` of a linear relation has to be smaller than `right`
+    InvalidRelation {
+        left: usize,
+        right: usize,
+    },
+}
+
+#[derive(Debug)]
+pub struct FileError {
+    pub pos: Position,
+    pub kind: FileErrorKind,
+}
+
+impl FileError {
+    pub fn new(pos: Position, kind: FileErrorKind) -> Self {
+        Self { pos, kind }
+    }
+}
+
+impl Display for FileErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileErrorKind::Syntax(msg) => write!(f, "syntax error: {}", msg.trim_end()),
+            FileErrorKind::UnknownKey { key, expected } => {
+                write!(f, "unknown key `{}`, expected one of {:?}", key, expected)
+            }
+            FileErrorKind::MissingKey(key) => write!(f, "missing key `{}`", key),
+            FileErrorKind::InvalidType {
+                key,
+                expected,
+                found,
+            } => write!(f, "`{}` should be {}, found {}", key, expected, found),
+            FileErrorKind::InvalidValue { key, value } => {
+                write!(f, "invalid value `{}` for `{}`", value, key)
+            }
+            FileErrorKind::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            FileErrorKind::DuplicateVariable(id) => {
+                write!(f, "variable with id {} is declared twice", id)
+            }
+            FileErrorKind::DuplicateName(name) => {
+                write!(f, "variable name `{}` is declared twice", name)
+            }
+            FileErrorKind::DuplicateConstraint(cid) => {
+                write!(f, "constraint id c{} is used twice", cid)
+            }
+            FileErrorKind::Boundary(e) => write!(f, "{}", e),
+            FileErrorKind::InvalidRelation { left, right } => write!(
+                f,
+                "left id {} has to be smaller than right id {}",
+                left, right
+            ),
+        }
+    }
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pos, self.kind)
+    }
+}
+
+impl std::error::Error for FileError {}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    /// the extension of the path is none of the known formats
+    UnknownFormat(PathBuf),
+    Invalid(Vec<FileError>),
+    /// the history file next to the model is broken
+    History(PathBuf, String),
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<Vec<FileError>> for LoadError {
+    fn from(value: Vec<FileError>) -> Self {
+        Self::Invalid(value)
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::UnknownFormat(path) => {
+                write!(f, "unknown model file format: {}", path.display())
+            }
+            LoadError::Invalid(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+            LoadError::History(path, e) => {
+                write!(f, "invalid history {}: {}", path.display(), e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
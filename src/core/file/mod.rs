@@ -0,0 +1,529 @@
+//! human editable model files
+//!
+//! ```toml
+//! [[variable]]
+//! id = 0
+//! name = "x"
+//!
+//! [[boundary]]
+//! cid = 0
+//! id = "x"
+//! top = { point = 20, equal = false }
+//! bot = { point = 0 }
+//!
+//! [[fixed]]
+//! id = 1
+//! value = 10
+//!
+//! [[linear]]
+//! # ( x * 2 + 3 ) <= #1
+//! left = "x"
+//! right = 1
+//! cmp = "<="
+//! mul = 2
+//! off = 3
+//!
+//! [[boundary]]
+//! # preferred range, may be left at a cost of 5 per unit
+//! id = 1
+//! top = { point = 12 }
+//! soft = { weight = 5, priority = 1 }
+//! ```
+//!
+//! the same layout is accepted as yaml. variables can be referred to by id or by declared name,
+//! limits are inclusive unless `equal = false`. constraints are hard unless marked `soft`.
+//! `cid` keeps the id of a constraint across saving and loading, constraints without one get
+//! fresh ids after the others.
+//!
+//! the edit history of a model is saved next to it, `model.toml` keeps it in
+//! `model.history.toml` ( see [`history_path`] ).
+
+mod errors;
+pub mod node;
+mod validate;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::{Yaml, YamlEmitter};
+
+use self::validate::Validator;
+use super::constraints::{
+    Boundary, Compare, DoubleConstrain, Fixed, Limit, Linear, SingleConstrain,
+};
+use super::model::{Constraint, ConstraintId, Model, ModelErrors, Soft};
+
+pub use self::errors::{FileError, FileErrorKind, LoadError, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// guess the format by the extension of `path`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Format::Toml),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// reference to a variable, either by id or by declared name
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VarRef {
+    Id(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableEntry {
+    pub id: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundaryEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<ConstraintId>,
+    pub id: VarRef,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<Limit<i64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot: Option<Limit<i64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft: Option<Soft>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixedEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<ConstraintId>,
+    pub id: VarRef,
+    pub value: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft: Option<Soft>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<ConstraintId>,
+    pub left: VarRef,
+    pub right: VarRef,
+    pub cmp: Compare,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mul: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub off: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft: Option<Soft>,
+}
+
+/// validated content of a model file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variable: Vec<VariableEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boundary: Vec<BoundaryEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixed: Vec<FixedEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linear: Vec<LinearEntry>,
+}
+
+impl ModelFile {
+    /// parse and validate `src`, reporting every problem found
+    pub fn parse(src: &str, format: Format) -> Result<Self, Vec<FileError>> {
+        let root = match format {
+            Format::Toml => node::parse_toml(src),
+            Format::Yaml => node::parse_yaml(src),
+        }
+        .map_err(|e| vec![e])?;
+
+        Self::from_node(&root)
+    }
+
+    /// validate a parsed document, see [`node::parse_toml`] and [`node::parse_yaml`]
+    pub fn from_node(root: &node::Node) -> Result<Self, Vec<FileError>> {
+        Validator::validate(root)
+    }
+
+    fn resolve(&self, var: &VarRef) -> usize {
+        match var {
+            VarRef::Id(id) => *id,
+            VarRef::Name(name) => self
+                .variable
+                .iter()
+                .find(|v| v.name.as_ref() == Some(name))
+                .map(|v| v.id)
+                .expect("names are checked on validation"),
+        }
+    }
+
+    /// build the model, the file has to be validated ( see [`ModelFile::parse`] )
+    pub fn build(&self) -> Result<Model, FileErrorKind> {
+        let mut model = Model::new();
+
+        for v in self.variable.iter() {
+            if let Some(name) = &v.name {
+                model.set_name(v.id, name.clone());
+            }
+        }
+        let mut constraints = Vec::new();
+        for b in self.boundary.iter() {
+            let boundary = Boundary::create(self.resolve(&b.id), b.top, b.bot)
+                .map_err(FileErrorKind::Boundary)?;
+            let c = Constraint::Single(SingleConstrain::Boundary(boundary));
+            constraints.push((b.cid, c, b.soft));
+        }
+        for f in self.fixed.iter() {
+            let c = Constraint::Single(SingleConstrain::Fixed(Fixed::new(
+                self.resolve(&f.id),
+                f.value,
+            )));
+            constraints.push((f.cid, c, f.soft));
+        }
+        for l in self.linear.iter() {
+            let (left, right) = (self.resolve(&l.left), self.resolve(&l.right));
+            if left >= right {
+                return Err(FileErrorKind::InvalidRelation { left, right });
+            }
+            let c = Constraint::Double(DoubleConstrain::LinearRelation(Linear::new(
+                left, right, l.mul, l.off, l.cmp,
+            )));
+            constraints.push((l.cid, c, l.soft));
+        }
+
+        // constraints with a `cid` first, the others get ids after them
+        let (with_id, without): (Vec<_>, Vec<_>) = constraints
+            .into_iter()
+            .partition(|(cid, _, _)| cid.is_some());
+        for (cid, c, soft) in with_id.into_iter().chain(without) {
+            let cid = match cid {
+                Some(cid) => match model.add_with_id(cid, c) {
+                    Ok(()) => cid,
+                    Err(ModelErrors::TakenId(cid)) => {
+                        return Err(FileErrorKind::DuplicateConstraint(cid.0))
+                    }
+                    Err(_) => unreachable!("relations are checked above"),
+                },
+                None => model.add(c).expect("relations are checked above"),
+            };
+            if let Some(soft) = soft {
+                if !model.set_soft(cid, soft) {
+                    return Err(FileErrorKind::InvalidValue {
+                        key: "weight".to_string(),
+                        value: soft.weight.to_string(),
+                    });
+                }
+            }
+        }
+        model.history.clear();
+
+        Ok(model)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("model file is always representable in toml")
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let value = toml::Value::try_from(self).expect("model file is always representable");
+        let mut out = String::new();
+        YamlEmitter::new(&mut out)
+            .dump(&yaml_value(value))
+            .expect("writing to string");
+        out.push('\n');
+        out
+    }
+
+    pub fn to_string(&self, format: Format) -> String {
+        match format {
+            Format::Toml => self.to_toml(),
+            Format::Yaml => self.to_yaml(),
+        }
+    }
+}
+
+fn yaml_value(value: toml::Value) -> Yaml {
+    match value {
+        toml::Value::String(s) => Yaml::String(s),
+        toml::Value::Integer(i) => Yaml::Integer(i),
+        toml::Value::Float(f) => Yaml::Real(f.to_string()),
+        toml::Value::Boolean(b) => Yaml::Boolean(b),
+        toml::Value::Datetime(d) => Yaml::String(d.to_string()),
+        toml::Value::Array(a) => Yaml::Array(a.into_iter().map(yaml_value).collect()),
+        toml::Value::Table(t) => {
+            let mut hash = Hash::new();
+            for (k, v) in t {
+                hash.insert(Yaml::String(k), yaml_value(v));
+            }
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+impl From<&Model> for ModelFile {
+    fn from(model: &Model) -> Self {
+        let var = |id: usize| match model.name(id) {
+            Some(name) => VarRef::Name(name.to_string()),
+            None => VarRef::Id(id),
+        };
+        let mut file = ModelFile::default();
+
+        let mut named: Vec<_> = model.names().iter().collect();
+        named.sort();
+        for (id, name) in named {
+            file.variable.push(VariableEntry {
+                id: *id,
+                name: Some(name.clone()),
+            });
+        }
+
+        // grouped by variables, in the order they were added
+        let mut constraints = model.constraints();
+        constraints.sort_by_key(|(_, c)| match c {
+            Constraint::Single(c) => (c.id(), 0),
+            Constraint::Double(c) => c.ids(),
+        });
+        for (cid, c) in constraints {
+            let soft = model.soft_of(cid);
+            match c {
+                Constraint::Single(SingleConstrain::Boundary(b)) => {
+                    file.boundary.push(BoundaryEntry {
+                        cid: Some(cid),
+                        id: var(b.id()),
+                        top: b.top.map(|t| t.limit),
+                        bot: b.bot.map(|b| b.limit),
+                        soft,
+                    })
+                }
+                Constraint::Single(SingleConstrain::Fixed(f)) => file.fixed.push(FixedEntry {
+                    cid: Some(cid),
+                    id: var(f.id),
+                    value: f.value,
+                    soft,
+                }),
+                Constraint::Double(DoubleConstrain::LinearRelation(l)) => {
+                    file.linear.push(LinearEntry {
+                        cid: Some(cid),
+                        left: var(l.left()),
+                        right: var(l.right()),
+                        cmp: l.cmp(),
+                        mul: l.mul(),
+                        off: l.off(),
+                        soft,
+                    })
+                }
+            }
+        }
+
+        file
+    }
+}
+
+impl Serialize for Model {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ModelFile::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Model {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let file = ModelFile::deserialize(deserializer)?;
+        // names are not checked by serde, look them up before building
+        for var in file
+            .boundary
+            .iter()
+            .map(|b| &b.id)
+            .chain(file.fixed.iter().map(|f| &f.id))
+            .chain(file.linear.iter().flat_map(|l| [&l.left, &l.right]))
+        {
+            if let VarRef::Name(name) = var {
+                if !file.variable.iter().any(|v| v.name.as_ref() == Some(name)) {
+                    return Err(serde::de::Error::custom(FileErrorKind::UnknownVariable(
+                        name.clone(),
+                    )));
+                }
+            }
+        }
+        file.build().map_err(serde::de::Error::custom)
+    }
+}
+
+/// constraint of a history file, variables are referred to by id
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ConstraintEntry {
+    Boundary(BoundaryEntry),
+    Fixed(FixedEntry),
+    Linear(LinearEntry),
+}
+
+impl Serialize for Constraint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entry = match self {
+            Constraint::Single(SingleConstrain::Boundary(b)) => {
+                ConstraintEntry::Boundary(BoundaryEntry {
+                    cid: None,
+                    id: VarRef::Id(b.id()),
+                    top: b.top.map(|t| t.limit),
+                    bot: b.bot.map(|b| b.limit),
+                    soft: None,
+                })
+            }
+            Constraint::Single(SingleConstrain::Fixed(f)) => ConstraintEntry::Fixed(FixedEntry {
+                cid: None,
+                id: VarRef::Id(f.id),
+                value: f.value,
+                soft: None,
+            }),
+            Constraint::Double(DoubleConstrain::LinearRelation(l)) => {
+                ConstraintEntry::Linear(LinearEntry {
+                    cid: None,
+                    left: VarRef::Id(l.left()),
+                    right: VarRef::Id(l.right()),
+                    cmp: l.cmp(),
+                    mul: l.mul(),
+                    off: l.off(),
+                    soft: None,
+                })
+            }
+        };
+        entry.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Constraint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = |var: VarRef| match var {
+            VarRef::Id(id) => Ok(id),
+            VarRef::Name(name) => Err(serde::de::Error::custom(format!(
+                "variables are referred to by id, found `{}`",
+                name
+            ))),
+        };
+
+        match ConstraintEntry::deserialize(deserializer)? {
+            ConstraintEntry::Boundary(b) => Boundary::create(id(b.id)?, b.top, b.bot)
+                .map(|b| Constraint::Single(SingleConstrain::Boundary(b)))
+                .map_err(|e| serde::de::Error::custom(FileErrorKind::Boundary(e))),
+            ConstraintEntry::Fixed(f) => Ok(Constraint::Single(SingleConstrain::Fixed(
+                Fixed::new(id(f.id)?, f.value),
+            ))),
+            ConstraintEntry::Linear(l) => {
+                let (left, right) = (id(l.left)?, id(l.right)?);
+                if left >= right {
+                    return Err(serde::de::Error::custom(FileErrorKind::InvalidRelation {
+                        left,
+                        right,
+                    }));
+                }
+                Ok(Constraint::Double(DoubleConstrain::LinearRelation(
+                    Linear::new(left, right, l.mul, l.off, l.cmp),
+                )))
+            }
+        }
+    }
+}
+
+/// path of the history saved with the model file `path`
+pub fn history_path(path: &Path) -> PathBuf {
+    path.with_extension("history.toml")
+}
+
+/// parse, validate and build a model from `src`
+pub fn load_str(src: &str, format: Format) -> Result<Model, Vec<FileError>> {
+    ModelFile::parse(src, format)?
+        .build()
+        .map_err(|e| vec![FileError::new(Position::default(), e)])
+}
+
+/// read a model file and its history, the format is chosen by the extension
+pub fn read(path: &Path) -> Result<Model, LoadError> {
+    let format = Format::from_path(path).ok_or_else(|| LoadError::UnknownFormat(path.into()))?;
+    let src = fs::read_to_string(path)?;
+    let mut model = load_str(&src, format)?;
+
+    let history = history_path(path);
+    if history.exists() {
+        let saved = toml::from_str(&fs::read_to_string(&history)?)
+            .map_err(|e| LoadError::History(history, e.to_string()))?;
+        model.set_history(saved);
+    }
+
+    Ok(model)
+}
+
+/// write `model` to `path` and its history next to it, the format is chosen by the extension
+pub fn write(path: &Path, model: &Model) -> Result<(), LoadError> {
+    let format = Format::from_path(path).ok_or_else(|| LoadError::UnknownFormat(path.into()))?;
+    fs::write(path, ModelFile::from(model).to_string(format))?;
+
+    let history = history_path(path);
+    if !model.history.is_empty() {
+        let src = toml::to_string(&model.history).expect("history is always representable");
+        fs::write(history, src)?;
+    } else if history.exists() {
+        // an outdated history would not match the model
+        fs::remove_file(history)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// path in a fresh temporary directory
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("abstaction-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn ids_and_history_survive_saving() {
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        // added in another order than the file groups them
+        let relation = model.add_linear(0, 1, Some(2), None, Compare::LTE);
+        let fixed = model.add_fixed(1, 4);
+        let boundary = model.add_boundary(0, None, None).unwrap();
+        model.set_soft(relation, Soft::new(3, 0));
+        model.remove(fixed);
+
+        let path = temp_path("model.toml");
+        write(&path, &model).unwrap();
+        let mut read = read(&path).unwrap();
+        assert_eq!(read.constraints(), model.constraints());
+        assert_eq!(read.soft_of(relation), Some(Soft::new(3, 0)));
+
+        // a new constraint does not take the id of the removed one
+        let added = read.add_fixed(0, 1);
+        assert!(added.0 > fixed.0);
+        read.undo();
+
+        // undo the removal, the softening and the additions
+        read.undo();
+        let four = Constraint::Single(SingleConstrain::Fixed(Fixed::new(1, 4)));
+        assert_eq!(read.get(fixed), Some(four));
+        read.undo();
+        assert_eq!(read.soft_of(relation), None);
+        read.undo();
+        assert_eq!(read.get(boundary), None);
+        assert!(read.get(relation).is_some());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
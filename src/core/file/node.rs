@@ -0,0 +1,219 @@
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+use super::errors::{FileError, FileErrorKind, Position};
+
+/// document tree with the position of every key and value,
+/// shared by the toml and yaml readers so that validation is format agnostic
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub value: Value,
+    pub pos: Position,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<Node>),
+    Table(Vec<(Key, Node)>),
+    /// value of a type the model file never uses ( datetime, null, .. )
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub struct Key {
+    pub name: String,
+    pub pos: Position,
+}
+
+impl Value {
+    /// name of the type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "boolean",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Table(_) => "table",
+            Value::Other => "unsupported value",
+        }
+    }
+}
+
+/// parse toml source into a [`Node`] tree
+pub fn parse_toml(src: &str) -> Result<Node, FileError> {
+    let root = DeTable::parse(src).map_err(|e| {
+        let pos = e
+            .span()
+            .map(|span| Position::from_offset(src, span.start))
+            .unwrap_or_default();
+        FileError::new(pos, FileErrorKind::Syntax(e.message().to_string()))
+    })?;
+
+    let pos = Position::from_offset(src, root.span().start);
+    Ok(Node {
+        value: toml_table(src, root.get_ref()),
+        pos,
+    })
+}
+
+fn toml_table(src: &str, table: &DeTable) -> Value {
+    Value::Table(
+        table
+            .iter()
+            .map(|(k, v)| {
+                (
+                    Key {
+                        name: k.get_ref().to_string(),
+                        pos: Position::from_offset(src, k.span().start),
+                    },
+                    toml_node(src, v),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn toml_node(src: &str, value: &Spanned<DeValue>) -> Node {
+    let pos = Position::from_offset(src, value.span().start);
+    let value = match value.get_ref() {
+        DeValue::String(s) => Value::String(s.to_string()),
+        DeValue::Integer(i) => match i64::from_str_radix(i.as_str(), i.radix()) {
+            Ok(i) => Value::Integer(i),
+            Err(_) => Value::Other,
+        },
+        DeValue::Float(f) => match f.as_str().parse() {
+            Ok(f) => Value::Float(f),
+            Err(_) => Value::Other,
+        },
+        DeValue::Boolean(b) => Value::Bool(*b),
+        DeValue::Datetime(_) => Value::Other,
+        DeValue::Array(array) => Value::Array(array.iter().map(|v| toml_node(src, v)).collect()),
+        DeValue::Table(table) => toml_table(src, table),
+    };
+
+    Node { value, pos }
+}
+
+/// parse yaml source into a [`Node`] tree
+pub fn parse_yaml(src: &str) -> Result<Node, FileError> {
+    let mut builder = YamlBuilder::default();
+    let mut parser = Parser::new_from_str(src);
+
+    parser.load(&mut builder, false).map_err(|e| {
+        let pos = Position::from_marker(e.marker());
+        FileError::new(pos, FileErrorKind::Syntax(e.info().to_string()))
+    })?;
+
+    if let Some(err) = builder.error {
+        return Err(err);
+    }
+
+    Ok(builder.root.unwrap_or(Node {
+        value: Value::Table(Vec::new()),
+        pos: Position::default(),
+    }))
+}
+
+/// partially built collection while receiving yaml events
+enum Frame {
+    Sequence(Position, Vec<Node>),
+    /// mapping with the key waiting for its value
+    Mapping(Position, Vec<(Key, Node)>, Option<Key>),
+}
+
+#[derive(Default)]
+struct YamlBuilder {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+    error: Option<FileError>,
+}
+
+impl YamlBuilder {
+    fn scalar(value: String, style: TScalarStyle) -> Value {
+        if style != TScalarStyle::Plain {
+            return Value::String(value);
+        }
+
+        if let Ok(i) = value.parse::<i64>() {
+            return Value::Integer(i);
+        }
+        if let Ok(f) = value.parse::<f64>() {
+            return Value::Float(f);
+        }
+        match value.as_str() {
+            "true" | "True" | "TRUE" => Value::Bool(true),
+            "false" | "False" | "FALSE" => Value::Bool(false),
+            "~" | "null" | "Null" | "NULL" | "" => Value::Other,
+            _ => Value::String(value),
+        }
+    }
+
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(Frame::Sequence(_, items)) => items.push(node),
+            Some(Frame::Mapping(_, entries, key)) => match key.take() {
+                Some(key) => entries.push((key, node)),
+                None => {
+                    let name = match node.value {
+                        Value::String(s) => s,
+                        Value::Integer(i) => i.to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        _ => {
+                            self.fail(node.pos, "mapping keys must be scalars");
+                            return;
+                        }
+                    };
+                    *key = Some(Key {
+                        name,
+                        pos: node.pos,
+                    });
+                }
+            },
+            None => self.root = Some(node),
+        }
+    }
+
+    fn fail(&mut self, pos: Position, msg: &str) {
+        if self.error.is_none() {
+            self.error = Some(FileError::new(pos, FileErrorKind::Syntax(msg.to_string())));
+        }
+    }
+}
+
+impl MarkedEventReceiver for YamlBuilder {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let pos = Position::from_marker(&mark);
+        match ev {
+            Event::Scalar(value, style, _, _) => self.push(Node {
+                value: Self::scalar(value, style),
+                pos,
+            }),
+            Event::SequenceStart(_, _) => self.stack.push(Frame::Sequence(pos, Vec::new())),
+            Event::MappingStart(_, _) => self.stack.push(Frame::Mapping(pos, Vec::new(), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let node = match self.stack.pop() {
+                    Some(Frame::Sequence(pos, items)) => Node {
+                        value: Value::Array(items),
+                        pos,
+                    },
+                    // a block mapping starts at the colon after its first key
+                    Some(Frame::Mapping(pos, entries, _)) => Node {
+                        pos: entries.first().map_or(pos, |(key, _)| key.pos.min(pos)),
+                        value: Value::Table(entries),
+                    },
+                    None => return,
+                };
+                self.push(node);
+            }
+            Event::Alias(_) => self.fail(pos, "aliases are not supported"),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,597 @@
+use std::collections::{HashMap, HashSet};
+
+use super::super::constraints::{Boundary, Compare, Limit};
+use super::super::model::{ConstraintId, Soft};
+use super::errors::{FileError, FileErrorKind, Position};
+use super::node::{Key, Node, Value};
+use super::{BoundaryEntry, FixedEntry, LinearEntry, ModelFile, VarRef, VariableEntry};
+
+const SECTIONS: &[&str] = &["variable", "boundary", "fixed", "linear"];
+const VARIABLE_KEYS: &[&str] = &["id", "name"];
+const BOUNDARY_KEYS: &[&str] = &["cid", "id", "top", "bot", "soft"];
+const LIMIT_KEYS: &[&str] = &["point", "equal"];
+const FIXED_KEYS: &[&str] = &["cid", "id", "value", "soft"];
+const LINEAR_KEYS: &[&str] = &["cid", "left", "right", "cmp", "mul", "off", "soft"];
+const SOFT_KEYS: &[&str] = &["weight", "priority"];
+
+/// entries of a table, looked up by key
+struct Fields<'a> {
+    pos: Position,
+    entries: Vec<&'a (Key, Node)>,
+}
+
+impl<'a> Fields<'a> {
+    fn get(&self, key: &str) -> Option<&'a Node> {
+        self.entries
+            .iter()
+            .find_map(|(k, v)| (k.name == key).then_some(v))
+    }
+}
+
+/// collects every error of a document instead of stopping at the first one
+#[derive(Default)]
+pub(super) struct Validator {
+    errors: Vec<FileError>,
+    /// declared names of variables
+    names: HashMap<String, usize>,
+    /// ids of the constraints
+    cids: HashSet<usize>,
+}
+
+impl Validator {
+    pub(super) fn validate(root: &Node) -> Result<ModelFile, Vec<FileError>> {
+        let mut validator = Self::default();
+        let mut file = ModelFile::default();
+
+        let sections = match &root.value {
+            Value::Table(entries) => entries,
+            other => {
+                validator.error(
+                    root.pos,
+                    FileErrorKind::InvalidType {
+                        key: "document".to_string(),
+                        expected: "a table",
+                        found: other.type_name(),
+                    },
+                );
+                return Err(validator.errors);
+            }
+        };
+
+        for (key, _) in sections.iter() {
+            if !SECTIONS.contains(&key.name.as_str()) {
+                validator.unknown_key(key, SECTIONS);
+            }
+        }
+
+        // variables come first in SECTIONS, so constraints above the declaration can use the names
+        let mut ids = HashSet::new();
+        for section in SECTIONS {
+            for (key, node) in sections.iter().filter(|(k, _)| k.name == *section) {
+                for entry in validator.array(key, node) {
+                    match *section {
+                        "variable" => {
+                            if let Some(v) = validator.variable(entry, &mut ids) {
+                                file.variable.push(v);
+                            }
+                        }
+                        "boundary" => {
+                            if let Some(b) = validator.boundary(entry) {
+                                file.boundary.push(b);
+                            }
+                        }
+                        "fixed" => {
+                            if let Some(f) = validator.fixed(entry) {
+                                file.fixed.push(f);
+                            }
+                        }
+                        _ => {
+                            if let Some(l) = validator.linear(entry) {
+                                file.linear.push(l);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if validator.errors.is_empty() {
+            Ok(file)
+        } else {
+            validator.errors.sort_by_key(|e| e.pos);
+            Err(validator.errors)
+        }
+    }
+
+    fn error(&mut self, pos: Position, kind: FileErrorKind) {
+        self.errors.push(FileError::new(pos, kind));
+    }
+
+    fn unknown_key(&mut self, key: &Key, expected: &'static [&'static str]) {
+        self.error(
+            key.pos,
+            FileErrorKind::UnknownKey {
+                key: key.name.clone(),
+                expected,
+            },
+        );
+    }
+
+    fn invalid_type(&mut self, key: &str, node: &Node, expected: &'static str) {
+        self.error(
+            node.pos,
+            FileErrorKind::InvalidType {
+                key: key.to_string(),
+                expected,
+                found: node.value.type_name(),
+            },
+        );
+    }
+
+    fn array<'a>(&mut self, key: &Key, node: &'a Node) -> &'a [Node] {
+        match &node.value {
+            Value::Array(items) => items,
+            _ => {
+                self.invalid_type(&key.name, node, "an array of tables");
+                &[]
+            }
+        }
+    }
+
+    fn fields<'a>(
+        &mut self,
+        key: &str,
+        node: &'a Node,
+        allowed: &'static [&'static str],
+    ) -> Option<Fields<'a>> {
+        let Value::Table(entries) = &node.value else {
+            self.invalid_type(key, node, "a table");
+            return None;
+        };
+
+        for (k, _) in entries.iter() {
+            if !allowed.contains(&k.name.as_str()) {
+                self.unknown_key(k, allowed);
+            }
+        }
+
+        Some(Fields {
+            pos: node.pos,
+            entries: entries.iter().collect(),
+        })
+    }
+
+    fn required<'a>(&mut self, fields: &Fields<'a>, key: &'static str) -> Option<&'a Node> {
+        let node = fields.get(key);
+        if node.is_none() {
+            self.error(fields.pos, FileErrorKind::MissingKey(key));
+        }
+        node
+    }
+
+    fn integer(&mut self, key: &str, node: &Node) -> Option<i64> {
+        match node.value {
+            Value::Integer(i) => Some(i),
+            _ => {
+                self.invalid_type(key, node, "an integer");
+                None
+            }
+        }
+    }
+
+    fn id(&mut self, key: &str, node: &Node) -> Option<usize> {
+        let i = self.integer(key, node)?;
+        match usize::try_from(i) {
+            Ok(id) => Some(id),
+            Err(_) => {
+                self.error(
+                    node.pos,
+                    FileErrorKind::InvalidValue {
+                        key: key.to_string(),
+                        value: i.to_string(),
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    /// id or name of a declared variable
+    fn var_ref(&mut self, key: &str, node: &Node) -> Option<(VarRef, usize)> {
+        match &node.value {
+            Value::String(name) => match self.names.get(name) {
+                Some(id) => Some((VarRef::Name(name.clone()), *id)),
+                None => {
+                    self.error(node.pos, FileErrorKind::UnknownVariable(name.clone()));
+                    None
+                }
+            },
+            Value::Integer(_) => self.id(key, node).map(|id| (VarRef::Id(id), id)),
+            _ => {
+                self.invalid_type(key, node, "an id or a variable name");
+                None
+            }
+        }
+    }
+
+    fn variable(&mut self, node: &Node, ids: &mut HashSet<usize>) -> Option<VariableEntry> {
+        let fields = self.fields("variable", node, VARIABLE_KEYS)?;
+        let id_node = self.required(&fields, "id")?;
+        let id = self.id("id", id_node)?;
+
+        if !ids.insert(id) {
+            self.error(id_node.pos, FileErrorKind::DuplicateVariable(id));
+        }
+
+        let name = match fields.get("name") {
+            Some(n) => match &n.value {
+                Value::String(name) => {
+                    if self.names.insert(name.clone(), id).is_some() {
+                        self.error(n.pos, FileErrorKind::DuplicateName(name.clone()));
+                    }
+                    Some(name.clone())
+                }
+                _ => {
+                    self.invalid_type("name", n, "a string");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Some(VariableEntry { id, name })
+    }
+
+    fn limit(&mut self, key: &str, node: &Node) -> Option<Limit<i64>> {
+        let fields = self.fields(key, node, LIMIT_KEYS)?;
+        let point = self.required(&fields, "point");
+        let point = point.and_then(|p| self.integer("point", p));
+
+        let equal = match fields.get("equal") {
+            Some(e) => match e.value {
+                Value::Bool(b) => Some(b),
+                _ => {
+                    self.invalid_type("equal", e, "a boolean");
+                    None
+                }
+            },
+            // limits are inclusive unless told otherwise
+            None => Some(true),
+        };
+
+        Some(Limit {
+            point: point?,
+            equal: equal?,
+        })
+    }
+
+    /// non negative integer fitting `T`
+    fn count<T: TryFrom<i64>>(&mut self, key: &str, node: &Node) -> Option<T> {
+        let i = self.integer(key, node)?;
+        match T::try_from(i) {
+            Ok(value) if i >= 0 => Some(value),
+            _ => {
+                self.error(
+                    node.pos,
+                    FileErrorKind::InvalidValue {
+                        key: key.to_string(),
+                        value: i.to_string(),
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    /// `cid` of a constraint, an absent one gets a fresh id when the model is built
+    fn cid(&mut self, fields: &Fields) -> Option<Option<ConstraintId>> {
+        let Some(node) = fields.get("cid") else {
+            return Some(None);
+        };
+        let cid = self.count::<usize>("cid", node)?;
+        if !self.cids.insert(cid) {
+            self.error(node.pos, FileErrorKind::DuplicateConstraint(cid));
+        }
+        Some(Some(ConstraintId(cid)))
+    }
+
+    /// `soft` mark of a constraint, an absent one is hard
+    fn soft(&mut self, fields: &Fields) -> Option<Option<Soft>> {
+        let Some(node) = fields.get("soft") else {
+            return Some(None);
+        };
+        let fields = self.fields("soft", node, SOFT_KEYS)?;
+        let default = Soft::default();
+        let weight = match fields.get("weight") {
+            Some(w) => self.count("weight", w),
+            None => Some(default.weight),
+        };
+        let priority = match fields.get("priority") {
+            Some(p) => self.count("priority", p),
+            None => Some(default.priority),
+        };
+
+        Some(Some(Soft::new(weight?, priority?)))
+    }
+
+    fn boundary(&mut self, node: &Node) -> Option<BoundaryEntry> {
+        let fields = self.fields("boundary", node, BOUNDARY_KEYS)?;
+        let cid = self.cid(&fields);
+        let id = self.required(&fields, "id");
+        let id = id.and_then(|id| self.var_ref("id", id));
+
+        let top = fields.get("top").map(|t| self.limit("top", t));
+        let bot = fields.get("bot").map(|b| self.limit("bot", b));
+        // a present but broken limit was already reported
+        let (top, bot) = (top.unwrap_or(None), bot.unwrap_or(None));
+        let soft = self.soft(&fields);
+        let (id, _) = id?;
+
+        if let Err(e) = Boundary::create(0, top, bot) {
+            self.error(fields.pos, FileErrorKind::Boundary(e));
+            return None;
+        }
+
+        Some(BoundaryEntry {
+            cid: cid?,
+            id,
+            top,
+            bot,
+            soft: soft?,
+        })
+    }
+
+    fn fixed(&mut self, node: &Node) -> Option<FixedEntry> {
+        let fields = self.fields("fixed", node, FIXED_KEYS)?;
+        let cid = self.cid(&fields);
+        let id = self.required(&fields, "id");
+        let id = id.and_then(|id| self.var_ref("id", id));
+        let value = self.required(&fields, "value");
+        let value = value.and_then(|v| self.integer("value", v));
+        let soft = self.soft(&fields);
+
+        Some(FixedEntry {
+            cid: cid?,
+            id: id?.0,
+            value: value?,
+            soft: soft?,
+        })
+    }
+
+    fn linear(&mut self, node: &Node) -> Option<LinearEntry> {
+        let fields = self.fields("linear", node, LINEAR_KEYS)?;
+        let cid = self.cid(&fields);
+        let left = self.required(&fields, "left");
+        let left = left.and_then(|l| self.var_ref("left", l));
+        let right = self.required(&fields, "right");
+        let right = right.and_then(|r| self.var_ref("right", r));
+
+        let cmp = self.required(&fields, "cmp").and_then(|c| match &c.value {
+            Value::String(s) => {
+                let cmp = Compare::from_symbol(s);
+                if cmp.is_none() {
+                    self.error(
+                        c.pos,
+                        FileErrorKind::InvalidValue {
+                            key: "cmp".to_string(),
+                            value: s.clone(),
+                        },
+                    );
+                }
+                cmp
+            }
+            _ => {
+                self.invalid_type("cmp", c, "a comparison ( <, <=, >, >=, = )");
+                None
+            }
+        });
+
+        let mul = fields.get("mul").map(|m| self.integer("mul", m));
+        let off = fields.get("off").map(|o| self.integer("off", o));
+        let soft = self.soft(&fields);
+
+        let ((left, left_id), (right, right_id)) = (left?, right?);
+        if left_id >= right_id {
+            self.error(
+                fields.pos,
+                FileErrorKind::InvalidRelation {
+                    left: left_id,
+                    right: right_id,
+                },
+            );
+            return None;
+        }
+
+        Some(LinearEntry {
+            cid: cid?,
+            left,
+            right,
+            cmp: cmp?,
+            mul: mul.unwrap_or(None),
+            off: off.unwrap_or(None),
+            soft: soft?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Format, ModelFile};
+
+    /// ( line, column, message ) of every error of `src`
+    fn errors(src: &str, format: Format) -> Vec<(usize, usize, String)> {
+        match ModelFile::parse(src, format) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors
+                .into_iter()
+                .map(|e| (e.pos.line, e.pos.column, e.kind.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn valid_file_builds() {
+        let src = r#"
+[[variable]]
+id = 0
+name = "x"
+
+[[boundary]]
+id = "x"
+top = { point = 20, equal = false }
+bot = { point = 0 }
+
+[[linear]]
+left = "x"
+right = 1
+cmp = "<="
+mul = 2
+soft = { weight = 5, priority = 1 }
+"#;
+        let file = ModelFile::parse(src, Format::Toml).unwrap();
+        let model = file.build().unwrap();
+        assert_eq!(model.constraints().len(), 2);
+        assert_eq!(model.id_of("x"), Some(0));
+    }
+
+    #[test]
+    fn every_error_is_reported_at_its_position() {
+        let src = r#"[[variable]]
+id = 0
+name = "x"
+colour = "red"
+
+[[fixed]]
+id = "y"
+value = "ten"
+
+[[linear]]
+left = 0
+right = 1
+cmp = "<>"
+"#;
+        assert_eq!(
+            errors(src, Format::Toml),
+            vec![
+                (
+                    4,
+                    1,
+                    "unknown key `colour`, expected one of [\"id\", \"name\"]".to_string()
+                ),
+                (7, 6, "unknown variable `y`".to_string()),
+                (
+                    8,
+                    9,
+                    "`value` should be an integer, found string".to_string()
+                ),
+                (13, 7, "invalid value `<>` for `cmp`".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_key_is_reported_at_the_entry() {
+        let src = "boundary:\n  - top:\n      point: 3\n";
+        assert_eq!(
+            errors(src, Format::Yaml),
+            vec![(2, 5, "missing key `id`".to_string())]
+        );
+    }
+
+    #[test]
+    fn unknown_section_and_wrong_types() {
+        let src = "constraint = 1\nfixed = 3\n";
+        assert_eq!(
+            errors(src, Format::Toml),
+            vec![
+                (
+                    1,
+                    1,
+                    "unknown key `constraint`, expected one of [\"variable\", \"boundary\", \"fixed\", \"linear\"]"
+                        .to_string()
+                ),
+                (2, 9, "`fixed` should be an array of tables, found integer".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_rejected() {
+        let src = "variable:\n  - id: 0\n    name: x\n  - id: 0\n    name: x\n";
+        assert_eq!(
+            errors(src, Format::Yaml),
+            vec![
+                (4, 9, "variable with id 0 is declared twice".to_string()),
+                (5, 11, "variable name `x` is declared twice".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn crossed_limits_and_reversed_relations() {
+        let src = r#"
+[[boundary]]
+id = 0
+top = { point = 1 }
+bot = { point = 5 }
+
+[[linear]]
+left = 2
+right = 1
+cmp = "<"
+"#;
+        let errors = errors(src, Format::Toml);
+        assert_eq!(errors.len(), 2);
+        assert_eq!((errors[0].0, errors[0].1), (2, 1));
+        assert_eq!(errors[0].2, "bottom limit 5 is not below top limit 1");
+        assert_eq!(
+            errors[1],
+            (
+                7,
+                1,
+                "left id 2 has to be smaller than right id 1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn negative_ids_and_weights_are_invalid() {
+        let src = "fixed:\n  - id: -1\n    value: 2\n    soft:\n      weight: -3\n";
+        assert_eq!(
+            errors(src, Format::Yaml),
+            vec![
+                (2, 9, "invalid value `-1` for `id`".to_string()),
+                (5, 15, "invalid value `-3` for `weight`".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_errors_have_a_position() {
+        let errors = errors("[[boundary]\nid = 0\n", Format::Toml);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].0, errors[0].1), (1, 12));
+        assert!(errors[0].2.starts_with("syntax error"));
+    }
+
+    #[test]
+    fn constraint_ids_are_kept_and_unique() {
+        let src = "fixed:\n  - cid: 4\n    id: 0\n    value: 1\n  - id: 1\n    value: 2\n";
+        let model = ModelFile::parse(src, Format::Yaml)
+            .unwrap()
+            .build()
+            .unwrap();
+        let cids: Vec<_> = model
+            .constraints()
+            .into_iter()
+            .map(|(cid, _)| cid.0)
+            .collect();
+        assert_eq!(cids, vec![4, 5]);
+
+        let src = "[[fixed]]\ncid = 2\nid = 0\nvalue = 1\n\n[[linear]]\ncid = 2\nleft = 0\nright = 1\ncmp = \"<\"\n";
+        assert_eq!(
+            errors(src, Format::Toml),
+            vec![(7, 7, "constraint id c2 is used twice".to_string())]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt::Display;
+
+use super::super::lp::{LpError, LpErrorKind};
+
+#[derive(Debug)]
+pub enum FznErrorKind {
+    Syntax(String),
+    UnknownIdentifier(String),
+    /// a constraint outside of the integer linear subset
+    UnsupportedConstraint(String),
+    /// a declaration of a type other than `int` or an integer range
+    UnsupportedType(String),
+    /// the constraint is supported, but can not be expressed by a `Model`
+    Unsupported(String),
+    InvalidBounds(String),
+    /// a domain, coefficient or constant that overflows an `i64` when it is translated
+    Overflow(String),
+}
+
+#[derive(Debug)]
+pub struct FznError {
+    /// 1-indexed line of the item
+    pub line: usize,
+    pub kind: FznErrorKind,
+}
+
+impl FznError {
+    pub fn new(line: usize, kind: FznErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl From<LpError> for FznError {
+    fn from(value: LpError) -> Self {
+        let kind = match value.kind {
+            LpErrorKind::Syntax(msg) => FznErrorKind::Syntax(msg),
+            LpErrorKind::Unsupported(what) => FznErrorKind::Unsupported(what),
+            LpErrorKind::NotInteger(n) => FznErrorKind::Syntax(format!("`{}` is no integer", n)),
+            LpErrorKind::Overflow(what) => FznErrorKind::Overflow(what),
+            LpErrorKind::Boundary(e) => FznErrorKind::InvalidBounds(e.to_string()),
+        };
+        Self::new(value.line, kind)
+    }
+}
+
+impl Display for FznErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FznErrorKind::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            FznErrorKind::UnknownIdentifier(name) => write!(f, "unknown identifier `{}`", name),
+            FznErrorKind::UnsupportedConstraint(name) => {
+                write!(f, "unsupported constraint `{}`", name)
+            }
+            FznErrorKind::UnsupportedType(ty) => write!(f, "unsupported type `{}`", ty),
+            FznErrorKind::Unsupported(what) => write!(f, "unsupported: {}", what),
+            FznErrorKind::InvalidBounds(msg) => write!(f, "invalid bounds: {}", msg),
+            FznErrorKind::Overflow(what) => write!(f, "{} overflows an i64", what),
+        }
+    }
+}
+
+impl Display for FznError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for FznError {}
@@ -0,0 +1,408 @@
+//! the integer linear subset of FlatZinc
+//!
+//! `var int` and `var lo..hi` declarations become variables, in declaration order, and the
+//! constraints `int_le`, `int_lt`, `int_eq`, `int_lin_le`, `int_lin_lt` and `int_lin_eq` are
+//! translated as long as they relate at most two variables. predicates, annotations and the
+//! solve item are ignored, anything else is reported with its line.
+
+mod errors;
+mod parser;
+
+use std::collections::HashMap;
+
+use self::parser::{Expr, Item};
+use super::lp::{LinearProgram, Row, Sense};
+use super::model::Model;
+
+pub use self::errors::{FznError, FznErrorKind};
+
+/// operand of a constraint
+#[derive(Debug, Clone, Copy)]
+enum Term {
+    /// index of a column
+    Var(usize),
+    Const(i64),
+}
+
+#[derive(Default)]
+struct Translator {
+    program: LinearProgram,
+    vars: HashMap<String, usize>,
+    params: HashMap<String, i64>,
+    arrays: HashMap<String, Vec<Term>>,
+    errors: Vec<FznError>,
+}
+
+impl Translator {
+    fn term(&self, expr: &Expr, line: usize) -> Result<Term, FznError> {
+        let unknown = |name: String| FznError::new(line, FznErrorKind::UnknownIdentifier(name));
+        match expr {
+            Expr::Int(i) => Ok(Term::Const(*i)),
+            Expr::Ident(name) => match (self.vars.get(name), self.params.get(name)) {
+                (Some(col), _) => Ok(Term::Var(*col)),
+                (None, Some(value)) => Ok(Term::Const(*value)),
+                (None, None) => Err(unknown(name.clone())),
+            },
+            Expr::Index(name, i) => self
+                .arrays
+                .get(name)
+                .and_then(|a| a.get(usize::try_from(i.checked_sub(1)?).ok()?))
+                .copied()
+                .ok_or_else(|| unknown(expr.to_string())),
+            _ => Err(FznError::new(
+                line,
+                FznErrorKind::UnsupportedType(expr.to_string()),
+            )),
+        }
+    }
+
+    fn array(&self, expr: &Expr, line: usize) -> Result<Vec<Term>, FznError> {
+        match expr {
+            Expr::Array(items) => items.iter().map(|e| self.term(e, line)).collect(),
+            Expr::Ident(name) => {
+                self.arrays.get(name).cloned().ok_or_else(|| {
+                    FznError::new(line, FznErrorKind::UnknownIdentifier(name.clone()))
+                })
+            }
+            _ => Err(FznError::new(
+                line,
+                FznErrorKind::Syntax(format!("expected an array, found `{}`", expr)),
+            )),
+        }
+    }
+
+    fn constant(&self, expr: &Expr, line: usize) -> Result<i64, FznError> {
+        match self.term(expr, line)? {
+            Term::Const(c) => Ok(c),
+            Term::Var(_) => Err(FznError::new(
+                line,
+                FznErrorKind::Syntax(format!("expected a constant, found `{}`", expr)),
+            )),
+        }
+    }
+
+    /// bounds of `var int`, `var lo..hi` and `var {a,b,..}` with contiguous values
+    fn bounds(ty: &Expr, line: usize) -> Result<(Option<i64>, Option<i64>), FznError> {
+        let unsupported = || FznError::new(line, FznErrorKind::UnsupportedType(ty.to_string()));
+        match ty {
+            Expr::Ident(name) if name == "int" => Ok((None, None)),
+            Expr::Range(lo, hi) if lo <= hi => Ok((Some(*lo), Some(*hi))),
+            Expr::Range(lo, hi) => Err(FznError::new(
+                line,
+                FznErrorKind::InvalidBounds(format!("{}..{} is empty", lo, hi)),
+            )),
+            Expr::Set(items) => {
+                let mut values = items
+                    .iter()
+                    .map(|e| match e {
+                        Expr::Int(i) => Some(*i),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(unsupported)?;
+                values.sort();
+                values.dedup();
+                let (Some(lo), Some(hi)) = (values.first(), values.last()) else {
+                    return Err(unsupported());
+                };
+                let span = hi.checked_sub(*lo).ok_or_else(|| {
+                    FznError::new(line, FznErrorKind::Overflow(format!("domain of {}", ty)))
+                })?;
+                match u64::try_from(span) == Ok(values.len() as u64 - 1) {
+                    true => Ok((Some(*lo), Some(*hi))),
+                    false => Err(unsupported()),
+                }
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn declare(
+        &mut self,
+        var: bool,
+        array: bool,
+        ty: &Expr,
+        name: &str,
+        value: Option<&Expr>,
+        line: usize,
+    ) -> Result<(), FznError> {
+        if self.vars.contains_key(name)
+            || self.params.contains_key(name)
+            || self.arrays.contains_key(name)
+        {
+            return Err(FznError::new(
+                line,
+                FznErrorKind::Syntax(format!("`{}` is declared twice", name)),
+            ));
+        }
+        Self::bounds(ty, line)?;
+
+        match (array, value) {
+            (true, Some(value)) => {
+                let terms = self.array(value, line)?;
+                if !var && terms.iter().any(|t| matches!(t, Term::Var(_))) {
+                    return Err(FznError::new(
+                        line,
+                        FznErrorKind::Syntax(format!("`{}` is no array of constants", name)),
+                    ));
+                }
+                self.arrays.insert(name.to_string(), terms);
+            }
+            (true, None) => {
+                return Err(FznError::new(
+                    line,
+                    FznErrorKind::Syntax(format!("array `{}` has no value", name)),
+                ))
+            }
+            (false, _) if var => {
+                let (lo, hi) = Self::bounds(ty, line)?;
+                let col = self.program.push_column(name, lo, hi);
+                self.program.ids.insert(name.to_string(), self.vars.len());
+                self.vars.insert(name.to_string(), col);
+                if let Some(value) = value {
+                    let term = self.term(value, line)?;
+                    self.push(
+                        name,
+                        vec![(Term::Var(col), 1), (term, -1)],
+                        Sense::Eq,
+                        0,
+                        line,
+                    )?;
+                }
+            }
+            (false, Some(value)) => {
+                let value = self.constant(value, line)?;
+                self.params.insert(name.to_string(), value);
+            }
+            (false, None) => {
+                return Err(FznError::new(
+                    line,
+                    FznErrorKind::Syntax(format!("parameter `{}` has no value", name)),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// add the row `sum(coef * term) sense rhs`
+    fn push(
+        &mut self,
+        name: &str,
+        terms: Vec<(Term, i64)>,
+        sense: Sense,
+        rhs: i64,
+        line: usize,
+    ) -> Result<(), FznError> {
+        let overflow = || FznError::new(line, FznErrorKind::Overflow(format!("`{}`", name)));
+        let mut rhs = rhs;
+        let mut vars: Vec<(usize, i64)> = Vec::new();
+        for (term, coef) in terms {
+            match term {
+                Term::Const(c) => {
+                    rhs = coef
+                        .checked_mul(c)
+                        .and_then(|product| rhs.checked_sub(product))
+                        .ok_or_else(overflow)?
+                }
+                Term::Var(col) => match vars.iter_mut().find(|(c, _)| *c == col) {
+                    Some((_, sum)) => *sum = sum.checked_add(coef).ok_or_else(overflow)?,
+                    None => vars.push((col, coef)),
+                },
+            }
+        }
+        vars.retain(|(_, coef)| *coef != 0);
+
+        if vars.is_empty() {
+            let holds = match sense {
+                Sense::Le => 0 <= rhs,
+                Sense::Ge => 0 >= rhs,
+                Sense::Eq => 0 == rhs,
+            };
+            return match holds {
+                true => Ok(()),
+                false => Err(FznError::new(
+                    line,
+                    FznErrorKind::Unsupported(format!("`{}` never holds", name)),
+                )),
+            };
+        }
+        if vars.len() > 2 {
+            return Err(FznError::new(
+                line,
+                FznErrorKind::Unsupported(format!("`{}` over {} variables", name, vars.len())),
+            ));
+        }
+
+        self.program.rows.push(Row {
+            name: name.to_string(),
+            terms: vars,
+            sense,
+            rhs,
+            line,
+        });
+        Ok(())
+    }
+
+    fn constraint(&mut self, call: &Expr, line: usize) -> Result<(), FznError> {
+        let Expr::Call(name, args) = call else {
+            return Err(FznError::new(
+                line,
+                FznErrorKind::Syntax(format!("expected a constraint, found `{}`", call)),
+            ));
+        };
+        let arity = |n: usize| match args.len() == n {
+            true => Ok(()),
+            false => Err(FznError::new(
+                line,
+                FznErrorKind::Syntax(format!("`{}` expects {} arguments", name, n)),
+            )),
+        };
+
+        match name.as_str() {
+            "int_le" | "int_lt" | "int_eq" => {
+                arity(2)?;
+                let (a, b) = (self.term(&args[0], line)?, self.term(&args[1], line)?);
+                let (sense, rhs) = match name.as_str() {
+                    "int_le" => (Sense::Le, 0),
+                    "int_lt" => (Sense::Le, -1),
+                    _ => (Sense::Eq, 0),
+                };
+                self.push(name, vec![(a, 1), (b, -1)], sense, rhs, line)
+            }
+            "int_lin_le" | "int_lin_lt" | "int_lin_eq" => {
+                arity(3)?;
+                let coefs = self
+                    .array(&args[0], line)?
+                    .into_iter()
+                    .map(|t| match t {
+                        Term::Const(c) => Ok(c),
+                        Term::Var(_) => Err(FznError::new(
+                            line,
+                            FznErrorKind::Syntax("coefficients have to be constants".to_string()),
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let terms = self.array(&args[1], line)?;
+                if coefs.len() != terms.len() {
+                    return Err(FznError::new(
+                        line,
+                        FznErrorKind::Syntax(format!(
+                            "{} coefficients for {} variables",
+                            coefs.len(),
+                            terms.len()
+                        )),
+                    ));
+                }
+                let rhs = self.constant(&args[2], line)?;
+                let (sense, rhs) = match name.as_str() {
+                    "int_lin_le" => (Sense::Le, Some(rhs)),
+                    "int_lin_lt" => (Sense::Le, rhs.checked_sub(1)),
+                    _ => (Sense::Eq, Some(rhs)),
+                };
+                let rhs = rhs.ok_or_else(|| {
+                    FznError::new(line, FznErrorKind::Overflow(format!("`{}`", name)))
+                })?;
+                self.push(
+                    name,
+                    terms.into_iter().zip(coefs).collect(),
+                    sense,
+                    rhs,
+                    line,
+                )
+            }
+            _ => Err(FznError::new(
+                line,
+                FznErrorKind::UnsupportedConstraint(name.clone()),
+            )),
+        }
+    }
+}
+
+/// read the integer linear subset of a FlatZinc model, reporting every unsupported item
+pub fn read(src: &str) -> Result<Model, Vec<FznError>> {
+    let items = parser::parse(src).map_err(|e| vec![e])?;
+    let mut translator = Translator::default();
+
+    for item in items.iter() {
+        let result = match item {
+            Item::Decl {
+                var,
+                array,
+                ty,
+                name,
+                value,
+                line,
+            } => translator.declare(*var, *array, ty, name, value.as_ref(), *line),
+            Item::Constraint { call, line } => translator.constraint(call, *line),
+        };
+        if let Err(e) = result {
+            translator.errors.push(e);
+        }
+    }
+
+    if !translator.errors.is_empty() {
+        return Err(translator.errors);
+    }
+    translator.program.to_model().map_err(|e| vec![e.into()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflows(src: &str) -> bool {
+        match read(src) {
+            Err(errors) => errors
+                .iter()
+                .any(|e| matches!(e.kind, FznErrorKind::Overflow(_))),
+            Ok(_) => false,
+        }
+    }
+
+    #[test]
+    fn linear_constraints_are_read() {
+        let model = read(
+            "var 0..10: x;\nvar int: y;\nconstraint int_lin_le([2, -1], [x, y], -3);\nsolve satisfy;\n",
+        )
+        .unwrap();
+        assert_eq!(model.constraints().len(), 2);
+        assert_eq!(model.name(0), Some("x"));
+    }
+
+    #[test]
+    fn overflowing_constants_are_errors() {
+        let min = i64::MIN;
+        assert!(overflows(&format!(
+            "var int: x;\nconstraint int_lin_le([{}, 1], [2, x], 0);\n",
+            min
+        )));
+        assert!(overflows(&format!(
+            "var int: x;\nconstraint int_lin_lt([1], [x], {});\n",
+            min
+        )));
+        assert!(overflows(&format!(
+            "var int: x;\nconstraint int_lin_le([{}, {}], [x, x], 0);\n",
+            i64::MAX,
+            i64::MAX
+        )));
+        assert!(overflows(&format!(
+            "var int: x;\nconstraint int_lt(x, {});\n",
+            min
+        )));
+    }
+
+    #[test]
+    fn domains_too_large_are_rejected() {
+        assert!(overflows(&format!(
+            "var {{{}, {}}}: x;\n",
+            i64::MIN,
+            i64::MAX
+        )));
+        // a set with a gap is no range
+        assert!(matches!(
+            read("var {1, 3}: x;\n").unwrap_err()[0].kind,
+            FznErrorKind::UnsupportedType(_)
+        ));
+        assert!(read("var {3, 1, 2}: x;\n").is_ok());
+    }
+}
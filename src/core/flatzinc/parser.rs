@@ -0,0 +1,370 @@
+use super::errors::{FznError, FznErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Float(String),
+    Str(String),
+    /// `..`
+    Range,
+    /// `::`
+    Annotation,
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Expr {
+    Int(i64),
+    Float(String),
+    Bool(bool),
+    Str(String),
+    Ident(String),
+    Range(i64, i64),
+    Set(Vec<Expr>),
+    Array(Vec<Expr>),
+    Index(String, i64),
+    Call(String, Vec<Expr>),
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |f: &mut std::fmt::Formatter<'_>, items: &[Expr]| {
+            for (i, e) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", e)?;
+            }
+            Ok(())
+        };
+        match self {
+            Expr::Int(i) => write!(f, "{}", i),
+            Expr::Float(s) => write!(f, "{}", s),
+            Expr::Bool(b) => write!(f, "{}", b),
+            Expr::Str(s) => write!(f, "\"{}\"", s),
+            Expr::Ident(s) => write!(f, "{}", s),
+            Expr::Range(a, b) => write!(f, "{}..{}", a, b),
+            Expr::Set(items) => {
+                write!(f, "{{")?;
+                list(f, items)?;
+                write!(f, "}}")
+            }
+            Expr::Array(items) => {
+                write!(f, "[")?;
+                list(f, items)?;
+                write!(f, "]")
+            }
+            Expr::Index(name, i) => write!(f, "{}[{}]", name, i),
+            Expr::Call(name, args) => {
+                write!(f, "{}(", name)?;
+                list(f, args)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Item {
+    /// `var type: name = value;` and `type: name = value;`
+    Decl {
+        var: bool,
+        array: bool,
+        ty: Expr,
+        name: String,
+        value: Option<Expr>,
+        line: usize,
+    },
+    Constraint {
+        call: Expr,
+        line: usize,
+    },
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>, FznError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '%' => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+                continue;
+            }
+            '.' if chars.next_if_eq(&'.').is_some() => Token::Range,
+            ':' if chars.next_if_eq(&':').is_some() => Token::Annotation,
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(d) => s.push(d),
+                        None => {
+                            return Err(FznError::new(
+                                line,
+                                FznErrorKind::Syntax("unterminated string".to_string()),
+                            ))
+                        }
+                    }
+                }
+                Token::Str(s)
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut number = c.to_string();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_alphanumeric()) {
+                    number.push(d);
+                }
+                // a single `.` continues a float, `..` starts a range
+                let mut lookahead = chars.clone();
+                if lookahead.next() == Some('.') && lookahead.peek() != Some(&'.') {
+                    chars.next();
+                    number.push('.');
+                    while let Some(d) =
+                        chars.next_if(|d| d.is_ascii_alphanumeric() || matches!(d, '+' | '-'))
+                    {
+                        number.push(d);
+                    }
+                    Token::Float(number)
+                } else {
+                    match number.parse() {
+                        Ok(i) => Token::Int(i),
+                        Err(_) => {
+                            return Err(FznError::new(
+                                line,
+                                FznErrorKind::Syntax(format!("invalid number `{}`", number)),
+                            ))
+                        }
+                    }
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_alphanumeric() || *d == '_') {
+                    ident.push(d);
+                }
+                Token::Ident(ident)
+            }
+            c if "[](){},:;=".contains(c) => Token::Punct(c),
+            c => {
+                return Err(FznError::new(
+                    line,
+                    FznErrorKind::Syntax(format!("unexpected `{}`", c)),
+                ))
+            }
+        };
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    at: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.at)
+            .or(self.tokens.last())
+            .map_or(1, |t| t.1)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at).map(|t| &t.0)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.at).map(|t| t.0.clone());
+        self.at += 1;
+        token
+    }
+
+    fn error(&self, msg: &str) -> FznError {
+        FznError::new(self.line(), FznErrorKind::Syntax(msg.to_string()))
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), FznError> {
+        match self.next() {
+            Some(Token::Punct(p)) if p == c => Ok(()),
+            _ => Err(self.error(&format!("expected `{}`", c))),
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(i)) if i == ident) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, FznError> {
+        match self.next() {
+            Some(Token::Ident(i)) => Ok(i),
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    fn list(&mut self, close: char) -> Result<Vec<Expr>, FznError> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expr()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, FznError> {
+        let expr = match self.next() {
+            Some(Token::Int(i)) => Expr::Int(i),
+            Some(Token::Float(f)) => Expr::Float(f),
+            Some(Token::Str(s)) => Expr::Str(s),
+            Some(Token::Punct('[')) => Expr::Array(self.list(']')?),
+            Some(Token::Punct('{')) => Expr::Set(self.list('}')?),
+            Some(Token::Ident(i)) if i == "true" => Expr::Bool(true),
+            Some(Token::Ident(i)) if i == "false" => Expr::Bool(false),
+            Some(Token::Ident(i)) => {
+                if self.eat('(') {
+                    Expr::Call(i, self.list(')')?)
+                } else if self.eat('[') {
+                    let index = match self.next() {
+                        Some(Token::Int(index)) => index,
+                        _ => return Err(self.error("expected an index")),
+                    };
+                    self.expect(']')?;
+                    Expr::Index(i, index)
+                } else {
+                    Expr::Ident(i)
+                }
+            }
+            _ => return Err(self.error("expected an expression")),
+        };
+
+        if self.peek() == Some(&Token::Range) {
+            self.at += 1;
+            let hi = self.expr()?;
+            return match (&expr, &hi) {
+                (Expr::Int(lo), Expr::Int(hi)) => Ok(Expr::Range(*lo, *hi)),
+                // float ranges are kept for the error message
+                _ => Ok(Expr::Call("range".to_string(), vec![expr, hi])),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn annotations(&mut self) -> Result<(), FznError> {
+        while self.peek() == Some(&Token::Annotation) {
+            self.at += 1;
+            self.expr()?;
+        }
+        Ok(())
+    }
+
+    /// `int`, `1..5`, `{1,2}`, `set of int`, ..
+    fn ty(&mut self) -> Result<Expr, FznError> {
+        if self.eat_ident("set") {
+            if !self.eat_ident("of") {
+                return Err(self.error("expected `of`"));
+            }
+            let inner = self.ty()?;
+            return Ok(Expr::Call("set".to_string(), vec![inner]));
+        }
+        self.expr()
+    }
+
+    fn skip_item(&mut self) -> Result<(), FznError> {
+        let mut depth = 0;
+        loop {
+            match self.next() {
+                Some(Token::Punct('(' | '[' | '{')) => depth += 1,
+                Some(Token::Punct(')' | ']' | '}')) => depth -= 1,
+                Some(Token::Punct(';')) if depth == 0 => return Ok(()),
+                Some(_) => {}
+                None => return Err(self.error("expected `;`")),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Result<Option<Item>, FznError> {
+        let line = self.line();
+
+        if self.eat_ident("predicate") || self.eat_ident("solve") {
+            self.skip_item()?;
+            return Ok(None);
+        }
+        if self.eat_ident("constraint") {
+            let call = self.expr()?;
+            self.annotations()?;
+            self.expect(';')?;
+            return Ok(Some(Item::Constraint { call, line }));
+        }
+
+        let array = self.eat_ident("array");
+        if array {
+            self.expect('[')?;
+            self.expr()?;
+            self.expect(']')?;
+            if !self.eat_ident("of") {
+                return Err(self.error("expected `of`"));
+            }
+        }
+        let var = self.eat_ident("var");
+        let ty = self.ty()?;
+        self.expect(':')?;
+        let name = self.ident()?;
+        self.annotations()?;
+        let value = match self.eat('=') {
+            true => Some(self.expr()?),
+            false => None,
+        };
+        self.expect(';')?;
+
+        Ok(Some(Item::Decl {
+            var,
+            array,
+            ty,
+            name,
+            value,
+            line,
+        }))
+    }
+}
+
+pub(super) fn parse(src: &str) -> Result<Vec<Item>, FznError> {
+    let mut parser = Parser {
+        tokens: tokenize(src)?,
+        at: 0,
+    };
+
+    let mut items = Vec::new();
+    while parser.peek().is_some() {
+        if let Some(item) = parser.item()? {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
@@ -0,0 +1,371 @@
+use std::fmt::Write;
+
+use super::super::model::Model;
+use super::errors::{LpError, LpErrorKind};
+use super::{integer, LinearProgram, Row, Sense};
+
+fn terms(program: &LinearProgram, row: &Row) -> String {
+    let mut out = String::new();
+    for (i, (col, coef)) in row.terms.iter().enumerate() {
+        let sign = match (i, *coef < 0) {
+            (0, false) => "",
+            (0, true) => "- ",
+            (_, false) => " + ",
+            (_, true) => " - ",
+        };
+        out.push_str(sign);
+        if coef.unsigned_abs() != 1 {
+            write!(out, "{} ", coef.unsigned_abs()).unwrap();
+        }
+        out.push_str(&program.columns[*col].name);
+    }
+    out
+}
+
+/// translate `model` into a CPLEX LP file
+pub fn write_lp(model: &Model) -> Result<String, LpError> {
+    let program = LinearProgram::from_model(model)?;
+    let mut out = String::new();
+
+    writeln!(out, "\\ generated by abstaction").unwrap();
+    if let Some(ids) = program.ids_comment() {
+        writeln!(out, "\\ {}", ids).unwrap();
+    }
+    writeln!(out, "Minimize").unwrap();
+    writeln!(out, " obj:").unwrap();
+
+    writeln!(out, "Subject To").unwrap();
+    for row in program.rows.iter() {
+        writeln!(
+            out,
+            " {}: {} {} {}",
+            row.name,
+            terms(&program, row),
+            row.sense.symbol(),
+            row.rhs
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "Bounds").unwrap();
+    for col in program.columns.iter() {
+        match (col.lo, col.hi) {
+            (Some(lo), Some(hi)) => writeln!(out, " {} <= {} <= {}", lo, col.name, hi),
+            (Some(lo), None) => writeln!(out, " {} >= {}", col.name, lo),
+            (None, Some(hi)) => writeln!(out, " -inf <= {} <= {}", col.name, hi),
+            (None, None) => writeln!(out, " {} free", col.name),
+        }
+        .unwrap();
+    }
+
+    if !program.columns.is_empty() {
+        writeln!(out, "Generals").unwrap();
+        let names: Vec<_> = program.columns.iter().map(|c| c.name.as_str()).collect();
+        writeln!(out, " {}", names.join(" ")).unwrap();
+    }
+    writeln!(out, "End").unwrap();
+
+    Ok(out)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Number(String),
+    Colon,
+    Sign(i64),
+    Cmp(Sense),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Objective,
+    Constraints,
+    Bounds,
+    /// generals, integers and binaries
+    Integers {
+        binary: bool,
+    },
+    End,
+}
+
+fn section(line: &str) -> Option<Section> {
+    let words: Vec<String> = line
+        .split_whitespace()
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+    let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+
+    match words.as_slice() {
+        ["minimize" | "minimum" | "min" | "maximize" | "maximum" | "max"] => {
+            Some(Section::Objective)
+        }
+        ["subject", "to"] | ["such", "that"] | ["st"] | ["s.t."] => Some(Section::Constraints),
+        ["bounds" | "bound"] => Some(Section::Bounds),
+        ["general" | "generals" | "gen" | "integer" | "integers"] => {
+            Some(Section::Integers { binary: false })
+        }
+        ["binary" | "binaries" | "bin"] => Some(Section::Integers { binary: true }),
+        ["end"] => Some(Section::End),
+        _ => None,
+    }
+}
+
+/// `word` alone on a line starts a section, so it can not name a column
+pub(super) fn is_header(word: &str) -> bool {
+    section(word).is_some()
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!\"#$%&()/,.;?@_`'{}|~".contains(c)
+}
+
+fn tokenize(text: &str, line: usize) -> Result<Vec<Token>, LpError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            ':' => tokens.push(Token::Colon),
+            '+' => tokens.push(Token::Sign(1)),
+            '-' => tokens.push(Token::Sign(-1)),
+            '<' | '>' | '=' => {
+                let next = chars.next_if(|d| matches!(d, '<' | '>' | '='));
+                let sense = match (c, next) {
+                    ('<', _) | ('=', Some('<')) => Sense::Le,
+                    ('>', _) | ('=', Some('>')) => Sense::Ge,
+                    _ => Sense::Eq,
+                };
+                tokens.push(Token::Cmp(sense));
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut number = c.to_string();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_digit() || *d == '.') {
+                    number.push(d);
+                }
+                if let Some(e) = chars.next_if(|d| matches!(d, 'e' | 'E')) {
+                    number.push(e);
+                    if let Some(s) = chars.next_if(|d| matches!(d, '+' | '-')) {
+                        number.push(s);
+                    }
+                    while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                        number.push(d);
+                    }
+                }
+                tokens.push(Token::Number(number));
+            }
+            c if is_name_char(c) => {
+                let mut name = c.to_string();
+                while let Some(d) = chars.next_if(|d| is_name_char(*d)) {
+                    name.push(d);
+                }
+                tokens.push(Token::Name(name));
+            }
+            c => {
+                return Err(LpError::new(
+                    line,
+                    LpErrorKind::Syntax(format!("unexpected `{}`", c)),
+                ))
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// number following the sign tokens, negated before it is parsed so that `- 9223372036854775808`
+/// is read
+fn signed(sign: i64, token: &str, line: usize) -> Result<i64, LpError> {
+    match sign < 0 {
+        true => integer(&format!("-{}", token), line),
+        false => integer(token, line),
+    }
+}
+
+fn is_infinity(name: &str) -> bool {
+    matches!(name.to_ascii_lowercase().as_str(), "inf" | "infinity")
+}
+
+/// signed number or infinity ( `None` )
+fn bound_value(tokens: &[Token], line: usize) -> Result<Option<Option<i64>>, LpError> {
+    let (sign, rest) = match tokens {
+        [Token::Sign(s), rest @ ..] => (*s, rest),
+        rest => (1, rest),
+    };
+    match rest {
+        [Token::Number(n)] => Ok(Some(Some(signed(sign, n, line)?))),
+        [Token::Name(n)] if is_infinity(n) => Ok(Some(None)),
+        _ => Ok(None),
+    }
+}
+
+fn read_bound(program: &mut LinearProgram, text: &str, line: usize) -> Result<(), LpError> {
+    let tokens = tokenize(text, line)?;
+    let syntax = || {
+        LpError::new(
+            line,
+            LpErrorKind::Syntax(format!("invalid bound `{}`", text)),
+        )
+    };
+
+    // the position of the column splits the bound in its parts
+    let Some(at) = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Name(n) if !is_infinity(n)))
+    else {
+        return Err(syntax());
+    };
+    let Token::Name(name) = &tokens[at] else {
+        unreachable!()
+    };
+    let col = program.column(name);
+    let (before, after) = (&tokens[..at], &tokens[at + 1..]);
+
+    if let [Token::Name(free)] = after {
+        if free.eq_ignore_ascii_case("free") && before.is_empty() {
+            program.columns[col].lo = None;
+            program.columns[col].hi = None;
+            return Ok(());
+        }
+    }
+
+    // value cmp x
+    if let [value @ .., Token::Cmp(sense)] = before {
+        let value = bound_value(value, line)?.ok_or_else(syntax)?;
+        program.set_bound(col, sense.flip(), value);
+    } else if !before.is_empty() {
+        return Err(syntax());
+    }
+    // x cmp value
+    if let [Token::Cmp(sense), value @ ..] = after {
+        let value = bound_value(value, line)?.ok_or_else(syntax)?;
+        program.set_bound(col, *sense, value);
+    } else if !after.is_empty() {
+        return Err(syntax());
+    }
+
+    Ok(())
+}
+
+/// parse every row of the constraint section, rows may span several lines
+fn read_rows(program: &mut LinearProgram, tokens: &[(Token, usize)]) -> Result<(), LpError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let line = tokens[i].1;
+        let syntax = |msg: &str| LpError::new(line, LpErrorKind::Syntax(msg.to_string()));
+
+        let name = match (&tokens[i].0, tokens.get(i + 1).map(|t| &t.0)) {
+            (Token::Name(n), Some(Token::Colon)) => {
+                i += 2;
+                n.clone()
+            }
+            _ => format!("r{}", program.rows.len()),
+        };
+
+        let mut terms = Vec::new();
+        let mut sign = 1;
+        let mut coef: Option<i64> = None;
+        let sense = loop {
+            match tokens.get(i).map(|t| &t.0) {
+                Some(Token::Sign(s)) => sign *= s,
+                Some(Token::Number(n)) => coef = Some(signed(sign, n, line)?),
+                Some(Token::Name(n)) => {
+                    let col = program.column(n);
+                    terms.push((col, coef.unwrap_or(sign)));
+                    sign = 1;
+                    coef = None;
+                }
+                Some(Token::Cmp(sense)) => break *sense,
+                Some(Token::Colon) | None => return Err(syntax("incomplete row")),
+            }
+            i += 1;
+        };
+        if coef.is_some() {
+            return Err(LpError::new(
+                line,
+                LpErrorKind::Unsupported(format!("constant on the left of row `{}`", name)),
+            ));
+        }
+        i += 1;
+
+        let mut sign = 1;
+        while let Some((Token::Sign(s), _)) = tokens.get(i) {
+            sign *= s;
+            i += 1;
+        }
+        let rhs = match tokens.get(i) {
+            Some((Token::Number(n), _)) => signed(sign, n, line)?,
+            _ => return Err(syntax("missing right hand side")),
+        };
+        i += 1;
+
+        program.rows.push(Row {
+            name,
+            terms,
+            sense,
+            rhs,
+            line,
+        });
+    }
+
+    Ok(())
+}
+
+/// read the linear subset of a CPLEX LP file, the objective is ignored
+pub fn read_lp(src: &str) -> Result<Model, LpError> {
+    let mut program = LinearProgram::default();
+    let mut current = None;
+    let mut rows = Vec::new();
+
+    for (n, raw) in src.lines().enumerate() {
+        let line = n + 1;
+        let (text, comment) = match raw.split_once('\\') {
+            Some((text, comment)) => (text, Some(comment)),
+            None => (raw, None),
+        };
+        if let Some(comment) = comment {
+            program.read_ids_comment(comment.trim());
+        }
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(s) = section(text) {
+            read_rows(&mut program, &rows)?;
+            rows.clear();
+            current = Some(s);
+            if s == Section::End {
+                break;
+            }
+            continue;
+        }
+
+        match current {
+            Some(Section::Objective) => {}
+            Some(Section::Constraints) => {
+                rows.extend(tokenize(text, line)?.into_iter().map(|t| (t, line)));
+            }
+            Some(Section::Bounds) => read_bound(&mut program, text, line)?,
+            Some(Section::Integers { binary }) => {
+                for name in text.split_whitespace() {
+                    let col = program.column(name);
+                    if binary {
+                        program.columns[col].lo = Some(0);
+                        program.columns[col].hi = Some(1);
+                    }
+                }
+            }
+            Some(Section::End) => break,
+            None => {
+                return Err(LpError::new(
+                    line,
+                    LpErrorKind::Unsupported(format!("`{}` outside of a section", text.trim())),
+                ))
+            }
+        }
+    }
+
+    read_rows(&mut program, &rows)?;
+    program.to_model()
+}
@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+use super::super::constraints::BoundaryError;
+
+#[derive(Debug)]
+pub enum LpErrorKind {
+    Syntax(String),
+    /// a row or section outside of the linear subset a `Model` can hold
+    Unsupported(String),
+    /// coefficient, bound or right hand side which is not an `i64`
+    NotInteger(String),
+    /// a bound, coefficient or right hand side that overflows an `i64` when converted
+    Overflow(String),
+    Boundary(BoundaryError<i64>),
+}
+
+#[derive(Debug)]
+pub struct LpError {
+    /// 1-indexed line, 0 when the problem is not bound to a line
+    pub line: usize,
+    pub kind: LpErrorKind,
+}
+
+impl LpError {
+    pub fn new(line: usize, kind: LpErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl Display for LpErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LpErrorKind::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            LpErrorKind::Unsupported(what) => write!(f, "unsupported: {}", what),
+            LpErrorKind::NotInteger(num) => write!(f, "`{}` is not an integer", num),
+            LpErrorKind::Overflow(what) => write!(f, "{} overflows an i64", what),
+            LpErrorKind::Boundary(e) => write!(f, "invalid bounds: {}", e),
+        }
+    }
+}
+
+impl Display for LpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "line {}: {}", self.line, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for LpError {}
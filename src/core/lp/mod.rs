@@ -0,0 +1,587 @@
+//! CPLEX LP and free MPS files
+//!
+//! `Boundary` becomes variable bounds ( intersected per variable ), `Fixed` an equality row and
+//! `Linear` a row `mul * left - right cmp -off`. every variable is integer, so strict
+//! comparisons are written as the next inclusive one.
+
+mod cplex;
+mod errors;
+mod mps;
+
+use std::collections::HashMap;
+
+use super::constraints::{Compare, DoubleConstrain, Limit, SingleConstrain};
+use super::model::{Model, ModelErrors};
+
+pub use self::cplex::{read_lp, write_lp};
+pub use self::errors::{LpError, LpErrorKind};
+pub use self::mps::{read_mps, write_mps};
+
+/// words of LP files besides the section headers, see [`cplex::is_header`]
+const KEYWORDS: &[&str] = &["free", "inf", "infinity", "subject", "such"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sense {
+    /// `<=`
+    Le,
+    /// `>=`
+    Ge,
+    /// `=`
+    Eq,
+}
+
+impl Sense {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Sense::Le => "<=",
+            Sense::Ge => ">=",
+            Sense::Eq => "=",
+        }
+    }
+
+    fn flip(&self) -> Self {
+        match self {
+            Sense::Le => Sense::Ge,
+            Sense::Ge => Sense::Le,
+            Sense::Eq => Sense::Eq,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    /// inclusive lower bound, `None` is minus infinity
+    pub lo: Option<i64>,
+    /// inclusive upper bound, `None` is infinity
+    pub hi: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub name: String,
+    /// ( index of column, coefficient )
+    pub terms: Vec<(usize, i64)>,
+    pub sense: Sense,
+    pub rhs: i64,
+    /// line the row was read from
+    pub line: usize,
+}
+
+/// linear program shared by the LP and MPS formats
+#[derive(Debug, Clone, Default)]
+pub struct LinearProgram {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Row>,
+    /// model id of columns, from the `abstaction-ids` comment
+    pub ids: HashMap<String, usize>,
+    /// index of columns by name
+    index: HashMap<String, usize>,
+}
+
+/// name usable in LP and MPS files
+fn is_plain_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let head_ok = matches!(chars.next(), Some(c) if (c.is_ascii_alphabetic() || c == '_') && c != 'e' && c != 'E');
+
+    head_ok
+        && name.len() <= 255
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        && !KEYWORDS.contains(&name.to_ascii_lowercase().as_str())
+        && !cplex::is_header(name)
+}
+
+/// id of a `v<id>` column
+fn id_name(name: &str) -> Option<usize> {
+    let digits = name.strip_prefix('v')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// parse a number which has to be an integer, `1e3` and `2.0` are accepted
+fn integer(token: &str, line: usize) -> Result<i64, LpError> {
+    if let Ok(i) = token.parse() {
+        return Ok(i);
+    }
+    match token.parse::<f64>() {
+        Ok(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => Ok(f as i64),
+        _ => Err(LpError::new(
+            line,
+            LpErrorKind::NotInteger(token.to_string()),
+        )),
+    }
+}
+
+fn floor_div(n: i64, d: i64) -> Option<i64> {
+    let q = n.checked_div(d)?;
+    if n % d != 0 && ((n < 0) != (d < 0)) {
+        Some(q - 1)
+    } else {
+        Some(q)
+    }
+}
+
+fn ceil_div(n: i64, d: i64) -> Option<i64> {
+    let q = n.checked_div(d)?;
+    if n % d != 0 && ((n < 0) == (d < 0)) {
+        Some(q + 1)
+    } else {
+        Some(q)
+    }
+}
+
+fn overflow(line: usize, what: String) -> LpError {
+    LpError::new(line, LpErrorKind::Overflow(what))
+}
+
+fn model_error(line: usize, e: ModelErrors<i64>) -> LpError {
+    match e {
+        ModelErrors::Boundary(e) => LpError::new(line, LpErrorKind::Boundary(e)),
+        ModelErrors::NotBoundary(_)
+        | ModelErrors::TakenId(_)
+        | ModelErrors::ReversedRelation { .. } => unreachable!("boundaries are only added"),
+    }
+}
+
+impl LinearProgram {
+    /// program of `model`, an error when a strict bound or comparison has no inclusive `i64`
+    /// form
+    pub fn from_model(model: &Model) -> Result<Self, LpError> {
+        let mut program = Self::default();
+        let mut index = HashMap::new();
+
+        for id in model.variables() {
+            let name = match model.name(id) {
+                Some(name) if is_plain_name(name) && id_name(name).is_none() => {
+                    program.ids.insert(name.to_string(), id);
+                    name.to_string()
+                }
+                _ => format!("v{}", id),
+            };
+            index.insert(id, program.columns.len());
+            program.index.insert(name.clone(), program.columns.len());
+            program.columns.push(Column {
+                name,
+                lo: None,
+                hi: None,
+            });
+        }
+
+        let mut ids: Vec<_> = model.single().keys().collect();
+        ids.sort();
+        for id in ids {
+            let col = index[id];
+            for c in model.single()[id].iter() {
+                match c {
+                    SingleConstrain::Boundary(b) => {
+                        let column = &mut program.columns[col];
+                        if let Some(bot) = b.bot {
+                            let lo = bot
+                                .limit
+                                .point
+                                .checked_add(i64::from(!bot.limit.equal))
+                                .ok_or_else(|| overflow(0, format!("bottom of {}", column.name)))?;
+                            column.lo = Some(column.lo.map_or(lo, |l| l.max(lo)));
+                        }
+                        if let Some(top) = b.top {
+                            let hi = top
+                                .limit
+                                .point
+                                .checked_sub(i64::from(!top.limit.equal))
+                                .ok_or_else(|| overflow(0, format!("top of {}", column.name)))?;
+                            column.hi = Some(column.hi.map_or(hi, |h| h.min(hi)));
+                        }
+                    }
+                    SingleConstrain::Fixed(f) => {
+                        program.push_row(vec![(col, 1)], Sense::Eq, f.value)
+                    }
+                }
+            }
+        }
+
+        let mut pairs: Vec<_> = model.double().keys().collect();
+        pairs.sort();
+        for pair in pairs {
+            for c in model.double()[pair].iter() {
+                let DoubleConstrain::LinearRelation(l) = c;
+                // mul * left + off cmp right  =>  mul * left - right cmp -off
+                let rhs = l.off().unwrap_or(0).checked_neg();
+                let (sense, rhs) = match l.cmp() {
+                    Compare::LT => (Sense::Le, rhs.and_then(|r| r.checked_sub(1))),
+                    Compare::LTE => (Sense::Le, rhs),
+                    Compare::GT => (Sense::Ge, rhs.and_then(|r| r.checked_add(1))),
+                    Compare::GTE => (Sense::Ge, rhs),
+                    Compare::EQ => (Sense::Eq, rhs),
+                };
+                let rhs = rhs.ok_or_else(|| {
+                    overflow(0, format!("right hand side of c{}", program.rows.len()))
+                })?;
+                let terms = vec![
+                    (index[&l.left()], l.mul().unwrap_or(1)),
+                    (index[&l.right()], -1),
+                ];
+                program.push_row(terms, sense, rhs);
+            }
+        }
+
+        Ok(program)
+    }
+
+    fn push_row(&mut self, terms: Vec<(usize, i64)>, sense: Sense, rhs: i64) {
+        self.rows.push(Row {
+            name: format!("c{}", self.rows.len()),
+            terms,
+            sense,
+            rhs,
+            line: 0,
+        });
+    }
+
+    /// `abstaction-ids: x=0 y=3`, mapping the named columns to model ids
+    fn ids_comment(&self) -> Option<String> {
+        let mut ids: Vec<_> = self.ids.iter().collect();
+        if ids.is_empty() {
+            return None;
+        }
+        ids.sort_by_key(|(_, id)| **id);
+        let pairs: Vec<_> = ids.iter().map(|(n, id)| format!("{}={}", n, id)).collect();
+        Some(format!("abstaction-ids: {}", pairs.join(" ")))
+    }
+
+    fn read_ids_comment(&mut self, comment: &str) {
+        let Some(pairs) = comment.strip_prefix("abstaction-ids:") else {
+            return;
+        };
+        for pair in pairs.split_whitespace() {
+            if let Some((name, id)) = pair.split_once('=') {
+                if let Ok(id) = id.parse() {
+                    self.ids.insert(name.to_string(), id);
+                }
+            }
+        }
+    }
+
+    /// `None` is an infinite bound
+    fn set_bound(&mut self, col: usize, sense: Sense, value: Option<i64>) {
+        let column = &mut self.columns[col];
+        match sense {
+            Sense::Le => column.hi = value,
+            Sense::Ge => column.lo = value,
+            Sense::Eq => {
+                column.lo = value;
+                column.hi = value;
+            }
+        }
+    }
+
+    /// index of the column, adding it when it is new
+    fn column(&mut self, name: &str) -> usize {
+        match self.index.get(name) {
+            Some(i) => *i,
+            None => {
+                self.index.insert(name.to_string(), self.columns.len());
+                self.columns.push(Column {
+                    name: name.to_string(),
+                    // default bounds of both formats
+                    lo: Some(0),
+                    hi: None,
+                });
+                self.columns.len() - 1
+            }
+        }
+    }
+
+    /// add a column with the given bounds, returns its index
+    pub fn push_column(&mut self, name: &str, lo: Option<i64>, hi: Option<i64>) -> usize {
+        let col = self.column(name);
+        self.columns[col].lo = lo;
+        self.columns[col].hi = hi;
+        col
+    }
+
+    /// model id of every column, `v<id>` and mapped names first, the rest after the largest id
+    fn column_ids(&self) -> Vec<usize> {
+        let known: Vec<Option<usize>> = self
+            .columns
+            .iter()
+            .map(|c| self.ids.get(&c.name).copied().or_else(|| id_name(&c.name)))
+            .collect();
+        let mut next = known.iter().flatten().max().map_or(0, |m| m + 1);
+
+        known
+            .into_iter()
+            .map(|id| {
+                id.unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect()
+    }
+
+    pub fn to_model(&self) -> Result<Model, LpError> {
+        let mut model = Model::new();
+        let ids = self.column_ids();
+
+        for (col, id) in self.columns.iter().zip(ids.iter()) {
+            if id_name(&col.name) != Some(*id) {
+                model.set_name(*id, col.name.clone());
+            }
+            let limit = |point| Some(Limit { point, equal: true });
+            match (col.lo, col.hi) {
+                (Some(lo), Some(hi)) if lo == hi => {
+                    model.add_fixed(*id, lo);
+                }
+                // crossed bounds, as written for boundaries that cannot all hold
+                (Some(lo), Some(hi)) if lo > hi => {
+                    model
+                        .add_boundary(*id, None, limit(lo))
+                        .map_err(|e| model_error(0, e))?;
+                    model
+                        .add_boundary(*id, limit(hi), None)
+                        .map_err(|e| model_error(0, e))?;
+                }
+                (None, None) => {}
+                (lo, hi) => {
+                    model
+                        .add_boundary(*id, hi.and_then(limit), lo.and_then(limit))
+                        .map_err(|e| model_error(0, e))?;
+                }
+            }
+        }
+
+        for row in self.rows.iter() {
+            let unsupported = || {
+                LpError::new(
+                    row.line,
+                    LpErrorKind::Unsupported(format!("row `{}`", row.name)),
+                )
+            };
+            let terms: Vec<_> = row
+                .terms
+                .iter()
+                .filter(|(_, coef)| *coef != 0)
+                .map(|(col, coef)| (ids[*col], *coef))
+                .collect();
+
+            match terms.as_slice() {
+                // a * x sense rhs
+                [(x, a)] => {
+                    let sense = if *a < 0 { row.sense.flip() } else { row.sense };
+                    let limit = |point| Some(Limit { point, equal: true });
+                    let too_large = || overflow(row.line, format!("row `{}`", row.name));
+                    match sense {
+                        Sense::Eq => match row.rhs.checked_rem(*a).ok_or_else(too_large)? {
+                            0 => model.add_fixed(*x, row.rhs / a),
+                            _ => return Err(unsupported()),
+                        },
+                        Sense::Le => {
+                            let hi = floor_div(row.rhs, *a).ok_or_else(too_large)?;
+                            model
+                                .add_boundary(*x, limit(hi), None)
+                                .map_err(|e| model_error(row.line, e))?
+                        }
+                        Sense::Ge => {
+                            let lo = ceil_div(row.rhs, *a).ok_or_else(too_large)?;
+                            model
+                                .add_boundary(*x, None, limit(lo))
+                                .map_err(|e| model_error(row.line, e))?
+                        }
+                    };
+                }
+                // a * x + b * y sense rhs, with the larger id scaled to 1
+                [(x, a), (y, b)] if x != y => {
+                    let ((left, a), (right, b)) = if x < y {
+                        ((*x, *a), (*y, *b))
+                    } else {
+                        ((*y, *b), (*x, *a))
+                    };
+                    let too_large = || overflow(row.line, format!("row `{}`", row.name));
+                    let (a_rem, rhs_rem) = (a.checked_rem(b), row.rhs.checked_rem(b));
+                    if a_rem.ok_or_else(too_large)? != 0 || rhs_rem.ok_or_else(too_large)? != 0 {
+                        return Err(unsupported());
+                    }
+                    // divide by -b:  (-a / b) * left + rhs / b  sense'  right
+                    let sense = if b > 0 { row.sense.flip() } else { row.sense };
+                    let mul = (a / b).checked_neg().ok_or_else(too_large)?;
+                    let off = row.rhs / b;
+                    let cmp = match sense {
+                        Sense::Le => Compare::LTE,
+                        Sense::Ge => Compare::GTE,
+                        Sense::Eq => Compare::EQ,
+                    };
+                    model.add_linear(
+                        left,
+                        right,
+                        (mul != 1).then_some(mul),
+                        (off != 0).then_some(off),
+                        cmp,
+                    );
+                }
+                _ => return Err(unsupported()),
+            }
+        }
+        model.history.clear();
+
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::Constraint;
+    use super::*;
+
+    fn constraints(model: &Model) -> Vec<Constraint> {
+        model.constraints().into_iter().map(|(_, c)| c).collect()
+    }
+
+    fn limit(point: i64, equal: bool) -> Option<Limit<i64>> {
+        Some(Limit { point, equal })
+    }
+
+    /// model as read back from both formats, which have to agree
+    fn round_trip(model: &Model) -> Model {
+        let lp = write_lp(model).unwrap();
+        let from_lp = read_lp(&lp).unwrap_or_else(|e| panic!("{}\n{}", e, lp));
+        let mps = write_mps(model).unwrap();
+        let from_mps = read_mps(&mps).unwrap_or_else(|e| panic!("{}\n{}", e, mps));
+        assert_eq!(constraints(&from_lp), constraints(&from_mps));
+
+        // a model read back is written the same way again
+        assert_eq!(write_lp(&from_lp).unwrap(), lp);
+        assert_eq!(write_mps(&from_mps).unwrap(), mps);
+        from_lp
+    }
+
+    #[test]
+    fn every_compare() {
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        model
+            .add_boundary(0, limit(10, false), limit(0, true))
+            .unwrap();
+        model.add_fixed(1, -4);
+        for cmp in [
+            Compare::LT,
+            Compare::LTE,
+            Compare::GT,
+            Compare::GTE,
+            Compare::EQ,
+        ] {
+            model.add_linear(0, 2, Some(2), Some(-3), cmp);
+        }
+
+        let read = round_trip(&model);
+        assert_eq!(read.name(0), Some("x"));
+        let mut expected = Model::new();
+        expected
+            .add_boundary(0, limit(9, true), limit(0, true))
+            .unwrap();
+        expected.add_fixed(1, -4);
+        // strict comparisons become inclusive ones
+        for (off, cmp) in [
+            (-2, Compare::LTE),
+            (-3, Compare::LTE),
+            (-4, Compare::GTE),
+            (-3, Compare::GTE),
+            (-3, Compare::EQ),
+        ] {
+            expected.add_linear(0, 2, Some(2), Some(off), cmp);
+        }
+        assert_eq!(constraints(&read), constraints(&expected));
+    }
+
+    #[test]
+    fn extreme_values() {
+        let mut model = Model::new();
+        model
+            .add_boundary(0, limit(i64::MAX, true), limit(i64::MIN, true))
+            .unwrap();
+        model.add_fixed(1, i64::MIN);
+        model.add_fixed(2, i64::MAX);
+
+        let read = round_trip(&model);
+        assert_eq!(constraints(&read), constraints(&model));
+    }
+
+    #[test]
+    fn crossed_bounds_are_read_back() {
+        let mut model = Model::new();
+        model
+            .add_boundary(0, limit(3, true), limit(0, true))
+            .unwrap();
+        model
+            .add_boundary(0, limit(9, true), limit(5, true))
+            .unwrap();
+
+        let read = round_trip(&model);
+        let mut expected = Model::new();
+        expected.add_boundary(0, None, limit(5, true)).unwrap();
+        expected.add_boundary(0, limit(3, true), None).unwrap();
+        assert_eq!(constraints(&read), constraints(&expected));
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        let overflows = |model: &Model| {
+            matches!(
+                LinearProgram::from_model(model),
+                Err(LpError {
+                    kind: LpErrorKind::Overflow(_),
+                    ..
+                })
+            )
+        };
+
+        let mut model = Model::new();
+        model.add_boundary(0, None, limit(i64::MAX, false)).unwrap();
+        assert!(overflows(&model));
+
+        let mut model = Model::new();
+        model.add_boundary(0, limit(i64::MIN, false), None).unwrap();
+        assert!(overflows(&model));
+
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, Some(i64::MIN), Compare::LTE);
+        assert!(overflows(&model));
+
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, Some(i64::MIN + 1), Compare::GT);
+        assert!(overflows(&model));
+
+        let read = read_lp("Subject To\n c0: - x = -9223372036854775808\nEnd\n");
+        assert!(matches!(
+            read,
+            Err(LpError {
+                kind: LpErrorKind::Overflow(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn section_headers_are_not_plain_names() {
+        for word in [
+            "max", "Minimum", "gen", "bin", "bound", "st", "s.t.", "End", "such",
+        ] {
+            assert!(!is_plain_name(word), "{}", word);
+        }
+        assert!(is_plain_name("maxi"));
+
+        let mut model = Model::new();
+        model.set_name(0, "max".to_string());
+        model.set_name(1, "gen".to_string());
+        model.set_name(2, "bound".to_string());
+        model.add_fixed(2, 4);
+        model.add_linear(0, 1, None, None, Compare::LTE);
+
+        // alone on the line of Generals, a header word would end the section
+        let lp = write_lp(&model).unwrap();
+        assert!(lp.contains("Generals\n v0 v1 v2\n"), "{}", lp);
+        let read = round_trip(&model);
+        assert_eq!(constraints(&read), constraints(&model));
+    }
+}
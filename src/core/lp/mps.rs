@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::super::model::Model;
+use super::errors::{LpError, LpErrorKind};
+use super::{integer, LinearProgram, Row, Sense};
+
+/// translate `model` into a free MPS file
+pub fn write_mps(model: &Model) -> Result<String, LpError> {
+    let program = LinearProgram::from_model(model)?;
+    let mut out = String::new();
+
+    writeln!(out, "* generated by abstaction").unwrap();
+    if let Some(ids) = program.ids_comment() {
+        writeln!(out, "* {}", ids).unwrap();
+    }
+    writeln!(out, "NAME abstaction").unwrap();
+
+    writeln!(out, "ROWS").unwrap();
+    writeln!(out, " N obj").unwrap();
+    for row in program.rows.iter() {
+        let kind = match row.sense {
+            Sense::Le => "L",
+            Sense::Ge => "G",
+            Sense::Eq => "E",
+        };
+        writeln!(out, " {} {}", kind, row.name).unwrap();
+    }
+
+    writeln!(out, "COLUMNS").unwrap();
+    writeln!(out, " M0 'MARKER' 'INTORG'").unwrap();
+    let mut entries = vec![Vec::new(); program.columns.len()];
+    for row in program.rows.iter() {
+        for (col, coef) in row.terms.iter() {
+            entries[*col].push((row.name.as_str(), *coef));
+        }
+    }
+    for (col, entries) in program.columns.iter().zip(entries) {
+        // the zero objective entry declares columns without any row
+        writeln!(out, " {} obj 0", col.name).unwrap();
+        for (row, coef) in entries {
+            writeln!(out, " {} {} {}", col.name, row, coef).unwrap();
+        }
+    }
+    writeln!(out, " M1 'MARKER' 'INTEND'").unwrap();
+
+    writeln!(out, "RHS").unwrap();
+    for row in program.rows.iter().filter(|r| r.rhs != 0) {
+        writeln!(out, " rhs {} {}", row.name, row.rhs).unwrap();
+    }
+
+    writeln!(out, "BOUNDS").unwrap();
+    for col in program.columns.iter() {
+        match (col.lo, col.hi) {
+            (Some(lo), Some(hi)) if lo == hi => writeln!(out, " FX bnd {} {}", col.name, lo),
+            (Some(lo), Some(hi)) => writeln!(out, " LO bnd {} {}", col.name, lo)
+                .and_then(|_| writeln!(out, " UP bnd {} {}", col.name, hi)),
+            (Some(lo), None) => writeln!(out, " LO bnd {} {}", col.name, lo),
+            (None, Some(hi)) => writeln!(out, " MI bnd {}", col.name)
+                .and_then(|_| writeln!(out, " UP bnd {} {}", col.name, hi)),
+            (None, None) => writeln!(out, " FR bnd {}", col.name),
+        }
+        .unwrap();
+    }
+    writeln!(out, "ENDATA").unwrap();
+
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Name,
+    Rows,
+    Columns,
+    Rhs,
+    Ranges,
+    Bounds,
+    ObjSense,
+}
+
+/// read the linear subset of a ( free ) MPS file, the objective is ignored
+pub fn read_mps(src: &str) -> Result<Model, LpError> {
+    let mut program = LinearProgram::default();
+    // row name to index in `program.rows`, `None` for objective rows
+    let mut rows: HashMap<String, Option<usize>> = HashMap::new();
+    let mut section = None;
+
+    for (n, raw) in src.lines().enumerate() {
+        let line = n + 1;
+        let syntax = || LpError::new(line, LpErrorKind::Syntax(format!("invalid line `{}`", raw)));
+        let unsupported =
+            |what: &str| LpError::new(line, LpErrorKind::Unsupported(what.to_string()));
+
+        if let Some(comment) = raw.strip_prefix('*') {
+            program.read_ids_comment(comment.trim());
+            continue;
+        }
+        let fields: Vec<&str> = raw.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+
+        if !raw.starts_with(char::is_whitespace) {
+            section = match fields[0].to_ascii_uppercase().as_str() {
+                "NAME" => Some(Section::Name),
+                "ROWS" => Some(Section::Rows),
+                "COLUMNS" => Some(Section::Columns),
+                "RHS" => Some(Section::Rhs),
+                "RANGES" => Some(Section::Ranges),
+                "BOUNDS" => Some(Section::Bounds),
+                "OBJSENSE" => Some(Section::ObjSense),
+                "ENDATA" => break,
+                other => return Err(unsupported(&format!("section `{}`", other))),
+            };
+            continue;
+        }
+
+        match section {
+            Some(Section::Name | Section::ObjSense) => {}
+            Some(Section::Rows) => {
+                let [kind, name] = fields.as_slice() else {
+                    return Err(syntax());
+                };
+                let sense = match kind.to_ascii_uppercase().as_str() {
+                    "N" => {
+                        rows.insert(name.to_string(), None);
+                        continue;
+                    }
+                    "L" => Sense::Le,
+                    "G" => Sense::Ge,
+                    "E" => Sense::Eq,
+                    _ => return Err(syntax()),
+                };
+                rows.insert(name.to_string(), Some(program.rows.len()));
+                program.rows.push(Row {
+                    name: name.to_string(),
+                    terms: Vec::new(),
+                    sense,
+                    rhs: 0,
+                    line,
+                });
+            }
+            Some(Section::Columns) => {
+                if fields.iter().any(|f| f.trim_matches('\'') == "MARKER") {
+                    continue;
+                }
+                let (name, entries) = fields.split_first().ok_or_else(syntax)?;
+                if entries.is_empty() || entries.len() % 2 != 0 {
+                    return Err(syntax());
+                }
+                let col = program.column(name);
+                for entry in entries.chunks(2) {
+                    match rows.get(entry[0]) {
+                        Some(Some(row)) => {
+                            let coef = integer(entry[1], line)?;
+                            program.rows[*row].terms.push((col, coef));
+                        }
+                        Some(None) => {}
+                        None => return Err(unsupported(&format!("undeclared row `{}`", entry[0]))),
+                    }
+                }
+            }
+            Some(Section::Rhs) => {
+                // the name of the rhs vector is optional
+                let entries = if fields.len() % 2 == 1 {
+                    &fields[1..]
+                } else {
+                    &fields[..]
+                };
+                for entry in entries.chunks(2) {
+                    match rows.get(entry[0]) {
+                        Some(Some(row)) => program.rows[*row].rhs = integer(entry[1], line)?,
+                        Some(None) => {}
+                        None => return Err(unsupported(&format!("undeclared row `{}`", entry[0]))),
+                    }
+                }
+            }
+            Some(Section::Ranges) => return Err(unsupported("RANGES")),
+            Some(Section::Bounds) => {
+                let kind = fields[0].to_ascii_uppercase();
+                let valued = matches!(kind.as_str(), "UP" | "LO" | "FX" | "LI" | "UI");
+                // the name of the bound vector is optional
+                let (name, value) = match (fields.len(), valued) {
+                    (4, true) => (fields[2], Some(fields[3])),
+                    (3, true) => (fields[1], Some(fields[2])),
+                    (3, false) => (fields[2], None),
+                    (2, false) => (fields[1], None),
+                    _ => return Err(syntax()),
+                };
+                let col = program.column(name);
+                let value = value.map(|v| integer(v, line)).transpose()?;
+                match kind.as_str() {
+                    "UP" | "UI" => program.set_bound(col, Sense::Le, value),
+                    "LO" | "LI" => program.set_bound(col, Sense::Ge, value),
+                    "FX" => program.set_bound(col, Sense::Eq, value),
+                    "FR" => {
+                        program.set_bound(col, Sense::Ge, None);
+                        program.set_bound(col, Sense::Le, None);
+                    }
+                    "MI" => program.set_bound(col, Sense::Ge, None),
+                    "PL" => program.set_bound(col, Sense::Le, None),
+                    "BV" => {
+                        program.set_bound(col, Sense::Ge, Some(0));
+                        program.set_bound(col, Sense::Le, Some(1));
+                    }
+                    other => return Err(unsupported(&format!("bound type `{}`", other))),
+                }
+            }
+            None => return Err(syntax()),
+        }
+    }
+
+    program.to_model()
+}
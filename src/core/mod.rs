@@ -1,2 +1,10 @@
-mod constraints;
+pub mod constraints;
+pub mod file;
+pub mod flatzinc;
+pub mod lp;
 pub mod model;
+pub mod series;
+pub mod smtlib;
+pub mod solver;
+pub mod statement;
+pub mod table;
@@ -0,0 +1,224 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+
+use super::{Constraint, ConstraintId, DoubleConstrain, Model, SingleConstrain, Soft};
+
+/// values of variables by id
+pub type Assignment = HashMap<usize, i64>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub cid: ConstraintId,
+    pub constraint: Constraint,
+    /// ( id, value ) of the constrained variables
+    pub values: Vec<(usize, i64)>,
+    /// how far the values are from satisfying the constraint, see [`Constraint::distance`]
+    pub distance: i64,
+    /// mark of a soft constraint, `None` for hard ones
+    pub soft: Option<Soft>,
+}
+
+/// result of [`Model::check`] and [`Plan::check`](super::Plan::check)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckReport {
+    /// number of constraints evaluated
+    pub checked: usize,
+    /// variables used by a constraint without a value, those constraints are not evaluated
+    pub missing: BTreeSet<usize>,
+    /// violated hard constraints, ordered by id
+    pub violations: Vec<Violation>,
+    /// sum of the distances of the violations
+    pub score: i64,
+    /// violated soft constraints, ordered by id
+    pub soft: Vec<Violation>,
+    /// weighted distances of the `soft` violations, summed by priority
+    pub penalties: BTreeMap<u32, i64>,
+    /// index in `violations` by variable, a relation is listed under both of its variables
+    pub by_variable: BTreeMap<usize, Vec<usize>>,
+    /// names of the reported variables
+    pub names: HashMap<usize, String>,
+}
+
+impl CheckReport {
+    /// every constraint was evaluated and every hard one holds
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.violations.is_empty()
+    }
+
+    /// weighted distances of the `soft` violations of every priority
+    pub fn penalty(&self) -> i64 {
+        self.penalties
+            .values()
+            .fold(0, |sum, p| sum.saturating_add(*p))
+    }
+
+    /// counts of the report in one line, e.g. `3 constraints checked, 1 violated, score 6`
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} constraints checked, {} violated",
+            self.checked,
+            self.violations.len()
+        );
+        if self.score > 0 {
+            out.push_str(&format!(", score {}", self.score));
+        }
+        if !self.soft.is_empty() {
+            out.push_str(&format!(
+                ", {} soft violated, penalty {}",
+                self.soft.len(),
+                self.penalty()
+            ));
+        }
+        out
+    }
+
+    /// one line describing a violation of the report, e.g. `c0: 0 <= x < 20 with x = 25, off by 6`
+    pub fn line(&self, v: &Violation) -> String {
+        let values: Vec<_> = v
+            .values
+            .iter()
+            .map(|(id, value)| format!("{} = {}", var_name(&self.names, *id), value))
+            .collect();
+        let mut line = format!(
+            "{}: {} with {}, off by {}",
+            v.cid,
+            formula(&v.constraint, |id| var_name(&self.names, id)),
+            values.join(", "),
+            v.distance
+        );
+        if let Some(soft) = v.soft {
+            line.push_str(&format!(
+                ", penalty {} ( weight {}, priority {} )",
+                soft.penalty(v.distance),
+                soft.weight,
+                soft.priority
+            ));
+        }
+        line
+    }
+}
+
+/// `x`, or `#id` for unnamed variables
+fn var_name(names: &HashMap<usize, String>, id: usize) -> String {
+    match names.get(&id) {
+        Some(name) => name.clone(),
+        None => format!("#{}", id),
+    }
+}
+
+/// formula of a constraint, e.g. `0 <= x < 20`, `x = 3` or `x * 2 + 1 <= y`
+fn formula(constraint: &Constraint, var: impl Fn(usize) -> String) -> String {
+    match constraint {
+        Constraint::Single(SingleConstrain::Boundary(b)) => {
+            let mut out = String::new();
+            if let Some(bot) = b.bot {
+                let cmp = if bot.limit.equal { "<=" } else { "<" };
+                out.push_str(&format!("{} {} ", bot.limit.point, cmp));
+            }
+            out.push_str(&var(b.id()));
+            if let Some(top) = b.top {
+                let cmp = if top.limit.equal { "<=" } else { "<" };
+                out.push_str(&format!(" {} {}", cmp, top.limit.point));
+            }
+            out
+        }
+        Constraint::Single(SingleConstrain::Fixed(f)) => format!("{} = {}", var(f.id), f.value),
+        Constraint::Double(DoubleConstrain::LinearRelation(l)) => l.formula(var),
+    }
+}
+
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary())?;
+        if !self.missing.is_empty() {
+            let missing: Vec<_> = self
+                .missing
+                .iter()
+                .map(|id| var_name(&self.names, *id))
+                .collect();
+            write!(f, "\nmissing values: {}", missing.join(", "))?;
+        }
+        for (id, violations) in self.by_variable.iter() {
+            write!(f, "\n{}:", var_name(&self.names, *id))?;
+            for v in violations.iter().map(|i| &self.violations[*i]) {
+                write!(f, "\n  {}", self.line(v))?;
+            }
+        }
+        if !self.soft.is_empty() {
+            write!(f, "\nsoft:")?;
+            for v in self.soft.iter() {
+                write!(f, "\n  {}", self.line(v))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Model {
+    /// formula of a constraint using the names of the variables
+    pub fn describe(&self, constraint: &Constraint) -> String {
+        formula(constraint, |id| var_name(&self.names, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Compare, Limit};
+    use super::*;
+
+    /// `0 <= x < 20`, `y = 3`, `x * 2 < y` and `z = 1`, checked with x = 25 and y = 4
+    fn report() -> CheckReport {
+        let mut model = Model::new();
+        model.set_name(0, "x");
+        model.set_name(1, "y");
+        model.set_name(2, "z");
+        let top = Limit {
+            point: 20,
+            equal: false,
+        };
+        let bot = Limit {
+            point: 0,
+            equal: true,
+        };
+        model.add_boundary(0, Some(top), Some(bot)).unwrap();
+        model.add_fixed(1, 3);
+        model.add_linear(0, 1, Some(2), None, Compare::LT);
+        model.add_fixed(2, 1);
+        model.check(&Assignment::from([(0, 25), (1, 4)]))
+    }
+
+    #[test]
+    fn violations_are_grouped_by_variable() {
+        let report = report();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.missing, BTreeSet::from([2]));
+        let cids: Vec<_> = report.violations.iter().map(|v| v.cid.0).collect();
+        assert_eq!(cids, vec![0, 1, 2]);
+        // the relation is listed under both of its variables
+        assert_eq!(
+            report.by_variable,
+            BTreeMap::from([(0, vec![0, 2]), (1, vec![1, 2])])
+        );
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn display_lists_violations_by_variable() {
+        let expected = "\
+3 constraints checked, 3 violated, score 54
+missing values: z
+x:
+  c0: 0 <= x < 20 with x = 25, off by 6
+  c2: x * 2 < y with x = 25, y = 4, off by 47
+y:
+  c1: y = 3 with y = 4, off by 1
+  c2: x * 2 < y with x = 25, y = 4, off by 47";
+        assert_eq!(report().to_string(), expected);
+
+        let mut model = Model::new();
+        model.add_fixed(0, 1);
+        let report = model.check(&Assignment::from([(0, 1)]));
+        assert!(report.is_ok());
+        assert_eq!(report.to_string(), "1 constraints checked, 0 violated");
+    }
+}
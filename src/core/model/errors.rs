@@ -1,10 +1,55 @@
+use std::fmt::{Debug, Display};
+
 use super::super::constraints::{BoundaryError, MinMax};
+use super::ConstraintId;
 
+#[derive(Debug)]
 pub enum ModelErrors<T>
 where
     T: MinMax + PartialOrd + Copy,
 {
     Boundary(BoundaryError<T>),
+    /// no boundary has the id
+    NotBoundary(ConstraintId),
+    /// the id is held by another constraint
+    TakenId(ConstraintId),
+    /// the left variable of a relation is not below the right one
+    ReversedRelation {
+        left: usize,
+        right: usize,
+    },
+}
+
+impl<T> Display for ModelErrors<T>
+where
+    T: MinMax + PartialOrd + Copy + Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelErrors::Boundary(e) => write!(f, "{}", e),
+            ModelErrors::NotBoundary(cid) => write!(f, "{} is no boundary", cid),
+            ModelErrors::TakenId(cid) => write!(f, "{} is already taken", cid),
+            ModelErrors::ReversedRelation { left, right } => write!(
+                f,
+                "the left variable #{} of a relation has to be below the right one #{}",
+                left, right
+            ),
+        }
+    }
+}
+
+impl<T> std::error::Error for ModelErrors<T>
+where
+    T: MinMax + PartialOrd + Copy + Debug + Display + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModelErrors::Boundary(e) => Some(e),
+            ModelErrors::NotBoundary(_)
+            | ModelErrors::TakenId(_)
+            | ModelErrors::ReversedRelation { .. } => None,
+        }
+    }
 }
 
 impl<T> From<BoundaryError<T>> for ModelErrors<T>
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Constraint, ConstraintId, Model, Soft};
+
+/// reversible edit of a `Model`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Command {
+    Add {
+        cid: ConstraintId,
+        constraint: Constraint,
+    },
+    Remove {
+        cid: ConstraintId,
+        constraint: Constraint,
+    },
+    Replace {
+        cid: ConstraintId,
+        old: Constraint,
+        new: Constraint,
+    },
+    /// `None` is no name
+    Rename {
+        var: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        old: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new: Option<String>,
+    },
+    /// `None` is a hard constraint
+    Soften {
+        cid: ConstraintId,
+        /// the constraint marked, to find it when its id does not match anymore
+        constraint: Constraint,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        old: Option<Soft>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        new: Option<Soft>,
+    },
+}
+
+impl Command {
+    /// the command reverting this one
+    pub fn inverse(&self) -> Command {
+        match self.clone() {
+            Command::Add { cid, constraint } => Command::Remove { cid, constraint },
+            Command::Remove { cid, constraint } => Command::Add { cid, constraint },
+            Command::Replace { cid, old, new } => Command::Replace {
+                cid,
+                old: new,
+                new: old,
+            },
+            Command::Rename { var, old, new } => Command::Rename {
+                var,
+                old: new,
+                new: old,
+            },
+            Command::Soften {
+                cid,
+                constraint,
+                old,
+                new,
+            } => Command::Soften {
+                cid,
+                constraint,
+                old: new,
+                new: old,
+            },
+        }
+    }
+}
+
+/// edits of a model, the last one is undone first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub undo: Vec<Command>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redo: Vec<Command>,
+}
+
+impl History {
+    /// record a new edit, which drops everything that could be redone
+    pub(super) fn record(&mut self, command: Command) {
+        self.undo.push(command);
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty() && self.redo.is_empty()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl Model {
+    /// replace the history, e.g. by a saved one. ids of constraints it refers to are not given
+    /// to new constraints, so undoing a removal restores the constraint under its old id
+    pub fn set_history(&mut self, history: History) {
+        let last = history
+            .undo
+            .iter()
+            .chain(history.redo.iter())
+            .filter_map(|command| match command {
+                Command::Add { cid, .. }
+                | Command::Remove { cid, .. }
+                | Command::Replace { cid, .. }
+                | Command::Soften { cid, .. } => Some(cid.0),
+                Command::Rename { .. } => None,
+            })
+            .max();
+        if let Some(last) = last {
+            self.next_id = self.next_id.max(last + 1);
+        }
+        self.history = history;
+    }
+
+    /// revert the last edit and return it.
+    /// `None` if there is nothing to undo, or the edit does not apply to the model anymore
+    /// ( e.g. a loaded history of another model ), it is dropped then
+    pub fn undo(&mut self) -> Option<Command> {
+        let command = self.history.undo.pop()?;
+        let reverted = self.apply(&command.inverse())?;
+        self.track(&reverted);
+        self.history.redo.push(reverted.inverse());
+        Some(command)
+    }
+
+    /// apply the last undone edit again and return it, `None` as for [`Model::undo`]
+    pub fn redo(&mut self) -> Option<Command> {
+        let command = self.history.redo.pop()?;
+        let applied = self.apply(&command)?;
+        self.track(&applied);
+        self.history.undo.push(applied.clone());
+        Some(applied)
+    }
+
+    /// apply `command` without recording it, returns it with the id it was applied to
+    pub(super) fn apply(&mut self, command: &Command) -> Option<Command> {
+        let applied = match command.clone() {
+            Command::Add { cid, constraint } => {
+                constraint.ordered().ok()?;
+                // keep the id unless it was taken in the meantime
+                let cid = match self.slots.contains_key(&cid) {
+                    true => ConstraintId(self.next_id),
+                    false => cid,
+                };
+                self.place(cid, constraint);
+                Command::Add { cid, constraint }
+            }
+            Command::Remove { cid, constraint } => {
+                let cid = self.find(cid, &constraint)?;
+                self.take(cid)?;
+                Command::Remove { cid, constraint }
+            }
+            Command::Replace { cid, old, new } => {
+                new.ordered().ok()?;
+                let cid = self.find(cid, &old)?;
+                self.swap(cid, new)?;
+                Command::Replace { cid, old, new }
+            }
+            Command::Rename { var, old, new } => {
+                match &new {
+                    Some(name) => self.names.insert(var, name.clone()),
+                    None => self.names.remove(&var),
+                };
+                Command::Rename { var, old, new }
+            }
+            Command::Soften {
+                cid,
+                constraint,
+                old,
+                new,
+            } => {
+                let cid = self.find(cid, &constraint)?;
+                match new {
+                    Some(soft) if soft.weight < 0 => return None,
+                    Some(soft) => self.soft.insert(cid, soft),
+                    None => self.soft.remove(&cid),
+                };
+                Command::Soften {
+                    cid,
+                    constraint,
+                    old,
+                    new,
+                }
+            }
+        };
+        Some(applied)
+    }
+
+    /// id of `constraint`, `cid` if it still holds it, the last equal constraint otherwise
+    fn find(&self, cid: ConstraintId, constraint: &Constraint) -> Option<ConstraintId> {
+        if self.get(cid).as_ref() == Some(constraint) {
+            return Some(cid);
+        }
+        self.constraints()
+            .into_iter()
+            .rev()
+            .find_map(|(id, c)| (c == *constraint).then_some(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::constraints::{Fixed, SingleConstrain};
+    use super::*;
+
+    fn fixed(id: usize, value: i64) -> Constraint {
+        Constraint::Single(SingleConstrain::Fixed(Fixed::new(id, value)))
+    }
+
+    #[test]
+    fn undo_and_redo_every_edit() {
+        let mut model = Model::new();
+        let cid = model.add_fixed(0, 1);
+        model.replace(cid, fixed(0, 2));
+        model.set_name(0, "x".to_string());
+        model.remove(cid);
+        assert_eq!(model.history.undo.len(), 4);
+
+        let undone: Vec<_> = std::iter::from_fn(|| model.undo()).collect();
+        assert_eq!(undone.len(), 4);
+        assert!(model.constraints().is_empty());
+        assert_eq!(model.name(0), None);
+
+        model.redo();
+        model.redo();
+        assert_eq!(model.get(cid), Some(fixed(0, 2)));
+        assert!(model.history.can_redo());
+
+        // a new edit drops what could be redone
+        model.add_fixed(1, 3);
+        assert!(!model.history.can_redo());
+        assert_eq!(model.redo(), None);
+    }
+
+    #[test]
+    fn edits_which_do_not_apply_are_dropped() {
+        let mut model = Model::new();
+        let cid = model.add_fixed(0, 1);
+        model.set_history(History {
+            undo: vec![Command::Replace {
+                cid: ConstraintId(7),
+                old: fixed(4, 4),
+                new: fixed(4, 5),
+            }],
+            redo: Vec::new(),
+        });
+        // the model has no `#4 = 5` to put back
+        assert_eq!(model.undo(), None);
+        assert!(model.history.is_empty());
+        // new constraints do not take the ids of the history
+        let history = model.history.clone();
+        model.set_history(History {
+            undo: vec![Command::Add {
+                cid: ConstraintId(7),
+                constraint: fixed(4, 4),
+            }],
+            ..history
+        });
+        assert_eq!(model.add_fixed(2, 2), ConstraintId(8));
+        assert_eq!(model.get(cid), Some(fixed(0, 1)));
+    }
+}
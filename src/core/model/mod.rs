@@ -1,23 +1,125 @@
+mod check;
 mod errors;
+mod history;
+mod plan;
+mod propagate;
+mod score;
+mod soft;
 
-use std::{collections::HashMap, i64};
+use std::collections::{BTreeSet, HashMap};
 
-use self::errors::ModelErrors;
+use serde::{Deserialize, Serialize};
+
+pub use self::check::{Assignment, CheckReport, Violation};
+pub use self::errors::ModelErrors;
+pub use self::history::{Command, History};
+pub use self::plan::Plan;
+pub use self::propagate::{Domain, Infeasible};
+pub use self::soft::Soft;
 
 use super::constraints::{
-    Compare, DoubleConstrainCheckError, DoubleConstrainCheckResult, SingleConstrainCheckError,
-    SingleConstrainCheckResult,
+    BoundaryError, DoubleConstrainCheckError, DoubleConstrainCheckResult,
+    SingleConstrainCheckError, SingleConstrainCheckResult,
+};
+
+pub use super::constraints::{
+    Boundary, Compare, DoubleConstrain, Fixed, Limit, Linear, SingleConstrain,
 };
 
-pub use super::constraints::{Boundary, DoubleConstrain, Fixed, Limit, Linear, SingleConstrain};
+/// stable handle of a constraint, returned when it is added to a `Model`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ConstraintId(pub usize);
+
+impl std::fmt::Display for ConstraintId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "c{}", self.0)
+    }
+}
+
+/// constraint of any kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constraint {
+    Single(SingleConstrain<i64>),
+    Double(DoubleConstrain<i64, i64>),
+}
+
+/// where the constraint of an id is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Single(usize),
+    Double((usize, usize)),
+}
+
+impl Constraint {
+    /// whether the constraint restricts `var`
+    pub fn involves(&self, var: usize) -> bool {
+        match self {
+            Constraint::Single(c) => c.id() == var,
+            Constraint::Double(c) => c.ids().0 == var || c.ids().1 == var,
+        }
+    }
+
+    /// error if the left id of a relation is not below the right one
+    fn ordered(&self) -> Result<(), ModelErrors<i64>> {
+        match self {
+            Constraint::Double(c) if c.ids().0 >= c.ids().1 => Err(ModelErrors::ReversedRelation {
+                left: c.ids().0,
+                right: c.ids().1,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn slot(&self) -> Slot {
+        match self {
+            Constraint::Single(c) => Slot::Single(c.id()),
+            Constraint::Double(c) => Slot::Double(c.ids()),
+        }
+    }
+}
+
+/// state saved by [`Model::push`]
+#[derive(Debug)]
+struct Scope {
+    /// edits made since the scope was opened
+    trail: Vec<Command>,
+    bounds: HashMap<usize, Domain>,
+    history: History,
+}
 
 /// T : type of variable := i64
 /// M : type of multiplier := i64
 /// O : type of offset(adder) := i64
+///
+/// constraints should be changed through the `add_*`, `remove` and `replace` methods only,
+/// so their ids stay valid
 #[derive(Debug)]
 pub struct Model {
-    pub single: HashMap<usize, Vec<SingleConstrain<i64>>>,
-    pub double: HashMap<(usize, usize), Vec<DoubleConstrain<i64, i64>>>,
+    single: HashMap<usize, Vec<SingleConstrain<i64>>>,
+    double: HashMap<(usize, usize), Vec<DoubleConstrain<i64, i64>>>,
+    /// optional human readable names of variables, changed through [`Model::set_name`] and
+    /// [`Model::remove_name`]
+    names: HashMap<usize, String>,
+    /// recorded edits, see [`Model::undo`] and [`Model::redo`]
+    pub history: History,
+    /// domains found by the last [`Model::propagate`]
+    pub bounds: HashMap<usize, Domain>,
+    /// soft constraints, every other one is required. changed through [`Model::set_soft`] and
+    /// [`Model::set_hard`], marks of removed constraints are kept for undoing the removal
+    soft: HashMap<ConstraintId, Soft>,
+    /// ids of the constraints in `single`, in the same order
+    single_ids: HashMap<usize, Vec<ConstraintId>>,
+    /// ids of the constraints in `double`, in the same order
+    double_ids: HashMap<(usize, usize), Vec<ConstraintId>>,
+    slots: HashMap<ConstraintId, Slot>,
+    next_id: usize,
+    scopes: Vec<Scope>,
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Model {
@@ -25,34 +127,135 @@ impl Model {
         Self {
             single: HashMap::new(),
             double: HashMap::new(),
+            names: HashMap::new(),
+            history: History::default(),
+            bounds: HashMap::new(),
+            soft: HashMap::new(),
+            single_ids: HashMap::new(),
+            double_ids: HashMap::new(),
+            slots: HashMap::new(),
+            next_id: 0,
+            scopes: Vec::new(),
+        }
+    }
+
+    /// open a scope, every edit made until the matching [`Model::pop`] is reverted by it.
+    /// the history of the scope starts empty
+    pub fn push(&mut self) {
+        self.scopes.push(Scope {
+            trail: Vec::new(),
+            bounds: self.bounds.clone(),
+            history: std::mem::take(&mut self.history),
+        });
+    }
+
+    /// revert the edits of the innermost scope and restore its bounds and history,
+    /// returns false if no scope is open
+    pub fn pop(&mut self) -> bool {
+        let Some(scope) = self.scopes.pop() else {
+            return false;
+        };
+        for command in scope.trail.iter().rev() {
+            self.apply(&command.inverse());
+        }
+        self.bounds = scope.bounds;
+        self.history = scope.history;
+        true
+    }
+
+    /// number of open scopes
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// keep an applied edit for the innermost scope
+    fn track(&mut self, command: &Command) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.trail.push(command.clone());
         }
     }
 
+    fn record(&mut self, command: Command) {
+        self.track(&command);
+        self.history.record(command);
+    }
+
+    pub fn set_name(&mut self, id: usize, name: impl Into<String>) {
+        let new = name.into();
+        let old = self.names.insert(id, new.clone());
+        self.record(Command::Rename {
+            var: id,
+            old,
+            new: Some(new),
+        });
+    }
+
+    /// remove the name of a variable, returns it
+    pub fn remove_name(&mut self, id: usize) -> Option<String> {
+        let old = self.names.remove(&id)?;
+        self.record(Command::Rename {
+            var: id,
+            old: Some(old.clone()),
+            new: None,
+        });
+        Some(old)
+    }
+
+    /// constraints of a single variable, by its id
+    pub fn single(&self) -> &HashMap<usize, Vec<SingleConstrain<i64>>> {
+        &self.single
+    }
+
+    /// relations, by the ids of their left and right variable
+    pub fn double(&self) -> &HashMap<(usize, usize), Vec<DoubleConstrain<i64, i64>>> {
+        &self.double
+    }
+
+    /// names of the variables, by id
+    pub fn names(&self) -> &HashMap<usize, String> {
+        &self.names
+    }
+
+    pub fn name(&self, id: usize) -> Option<&str> {
+        self.names.get(&id).map(|n| n.as_str())
+    }
+
+    /// ids of every variable that is named or constrained
+    pub fn variables(&self) -> BTreeSet<usize> {
+        let mut ids: BTreeSet<usize> = self.names.keys().copied().collect();
+        ids.extend(self.single.keys());
+        ids.extend(self.double.keys().flat_map(|(l, r)| [*l, *r]));
+        ids
+    }
+
+    /// find id of variable by its name
+    pub fn id_of(&self, name: &str) -> Option<usize> {
+        self.names
+            .iter()
+            .find_map(|(id, n)| (n == name).then_some(*id))
+    }
+
+    /// variable referred to by its name or by its id, as `3` or `#3`
+    pub fn lookup(&self, var: &str) -> Option<usize> {
+        self.id_of(var)
+            .or_else(|| var.strip_prefix('#').unwrap_or(var).parse().ok())
+    }
+
     pub fn add_boundary(
         &mut self,
         id: usize,
         top: Option<Limit<i64>>,
         bot: Option<Limit<i64>>,
-    ) -> Result<(), ModelErrors<i64>> {
-        if let Some(vec) = self.single.get_mut(&id) {
-            vec.push(SingleConstrain::Boundary(Boundary::create(id, top, bot)?))
-        } else {
-            self.single.insert(id, Vec::new());
+    ) -> Result<ConstraintId, ModelErrors<i64>> {
+        let boundary = Boundary::create(id, top, bot)?;
 
-            return self.add_boundary(id, top, bot);
-        }
-
-        Ok(())
+        Ok(self.insert(Constraint::Single(SingleConstrain::Boundary(boundary))))
     }
 
-    pub fn add_fixed(&mut self, id: usize, value: i64) {
-        if let Some(vec) = self.single.get_mut(&id) {
-            vec.push(SingleConstrain::Fixed(Fixed::new(id, value)));
-        } else {
-            self.single.insert(id, Vec::new());
-
-            self.add_fixed(id, value);
-        }
+    pub fn add_fixed(&mut self, id: usize, value: i64) -> ConstraintId {
+        self.insert(Constraint::Single(SingleConstrain::Fixed(Fixed::new(
+            id, value,
+        ))))
     }
 
     pub fn add_linear(
@@ -62,18 +265,190 @@ impl Model {
         mul: Option<i64>,
         off: Option<i64>,
         cmp: Compare,
-    ) {
+    ) -> ConstraintId {
         debug_assert!(left_id < right_id);
 
-        if let Some(vec) = self.double.get_mut(&(left_id, right_id)) {
-            vec.push(DoubleConstrain::LinearRelation(Linear::new(
-                left_id, right_id, mul, off, cmp,
-            )));
-        } else {
-            self.double.insert((left_id, right_id), Vec::new());
+        self.insert(Constraint::Double(DoubleConstrain::LinearRelation(
+            Linear::new(left_id, right_id, mul, off, cmp),
+        )))
+    }
 
-            self.add_linear(left_id, right_id, mul, off, cmp);
+    /// add a constraint of any kind, the left id of a relation has to be below the right one
+    pub fn add(&mut self, constraint: Constraint) -> Result<ConstraintId, ModelErrors<i64>> {
+        constraint.ordered()?;
+        Ok(self.insert(constraint))
+    }
+
+    /// add a constraint under the given id, see [`Model::add`]
+    pub fn add_with_id(
+        &mut self,
+        cid: ConstraintId,
+        constraint: Constraint,
+    ) -> Result<(), ModelErrors<i64>> {
+        constraint.ordered()?;
+        if self.slots.contains_key(&cid) {
+            return Err(ModelErrors::TakenId(cid));
         }
+        self.place(cid, constraint);
+        self.record(Command::Add { cid, constraint });
+        Ok(())
+    }
+
+    /// set the top limit of the boundary `cid`
+    pub fn set_top(&mut self, cid: ConstraintId, top: Limit<i64>) -> Result<(), ModelErrors<i64>> {
+        self.edit_boundary(cid, |b| b.set_top(top))
+    }
+
+    /// set the bottom limit of the boundary `cid`
+    pub fn set_bot(&mut self, cid: ConstraintId, bot: Limit<i64>) -> Result<(), ModelErrors<i64>> {
+        self.edit_boundary(cid, |b| b.set_bot(bot))
+    }
+
+    /// set both limits of the boundary `cid`
+    pub fn update_boundary(
+        &mut self,
+        cid: ConstraintId,
+        top: Limit<i64>,
+        bot: Limit<i64>,
+    ) -> Result<(), ModelErrors<i64>> {
+        self.edit_boundary(cid, |b| b.update(top, bot))
+    }
+
+    /// edit a copy of the boundary, stored as a replacement when `edit` succeeds
+    fn edit_boundary(
+        &mut self,
+        cid: ConstraintId,
+        edit: impl FnOnce(&mut Boundary<i64>) -> Result<(), BoundaryError<i64>>,
+    ) -> Result<(), ModelErrors<i64>> {
+        let Some(Constraint::Single(SingleConstrain::Boundary(mut boundary))) = self.get(cid)
+        else {
+            return Err(ModelErrors::NotBoundary(cid));
+        };
+        edit(&mut boundary)?;
+        self.replace(cid, Constraint::Single(SingleConstrain::Boundary(boundary)));
+
+        Ok(())
+    }
+
+    fn insert(&mut self, constraint: Constraint) -> ConstraintId {
+        let cid = ConstraintId(self.next_id);
+        self.place(cid, constraint);
+        self.record(Command::Add { cid, constraint });
+        cid
+    }
+
+    /// store `constraint` under `cid`, after the constraints of the same variables
+    fn place(&mut self, cid: ConstraintId, constraint: Constraint) {
+        self.next_id = self.next_id.max(cid.0 + 1);
+        self.slots.insert(cid, constraint.slot());
+        match constraint {
+            Constraint::Single(c) => {
+                self.single.entry(c.id()).or_default().push(c);
+                self.single_ids.entry(c.id()).or_default().push(cid);
+            }
+            Constraint::Double(c) => {
+                debug_assert!(c.ids().0 < c.ids().1);
+                self.double.entry(c.ids()).or_default().push(c);
+                self.double_ids.entry(c.ids()).or_default().push(cid);
+            }
+        }
+    }
+
+    /// ( slot, index in the vec of the slot ) of `cid`
+    fn locate(&self, cid: ConstraintId) -> Option<(Slot, usize)> {
+        let slot = *self.slots.get(&cid)?;
+        let ids = match slot {
+            Slot::Single(id) => self.single_ids.get(&id)?,
+            Slot::Double(pair) => self.double_ids.get(&pair)?,
+        };
+        Some((slot, ids.iter().position(|c| *c == cid)?))
+    }
+
+    pub fn get(&self, cid: ConstraintId) -> Option<Constraint> {
+        match self.locate(cid)? {
+            (Slot::Single(id), at) => Some(Constraint::Single(self.single[&id][at])),
+            (Slot::Double(pair), at) => Some(Constraint::Double(self.double[&pair][at])),
+        }
+    }
+
+    /// every constraint with its id, ordered by id
+    pub fn constraints(&self) -> Vec<(ConstraintId, Constraint)> {
+        let mut ids: Vec<_> = self.slots.keys().copied().collect();
+        ids.sort();
+        ids.into_iter()
+            .filter_map(|cid| Some((cid, self.get(cid)?)))
+            .collect()
+    }
+
+    /// remove the constraint, `None` if `cid` is unknown
+    pub fn remove(&mut self, cid: ConstraintId) -> Option<Constraint> {
+        let constraint = self.take(cid)?;
+        self.record(Command::Remove { cid, constraint });
+        Some(constraint)
+    }
+
+    /// replace the constraint keeping its id, returns the old one. `None` if `cid` is unknown or
+    /// `constraint` a relation with the left id above the right one, the model is left unchanged
+    /// then
+    pub fn replace(&mut self, cid: ConstraintId, constraint: Constraint) -> Option<Constraint> {
+        constraint.ordered().ok()?;
+        let old = self.swap(cid, constraint)?;
+        self.record(Command::Replace {
+            cid,
+            old,
+            new: constraint,
+        });
+        Some(old)
+    }
+
+    /// remove without recording
+    fn take(&mut self, cid: ConstraintId) -> Option<Constraint> {
+        let (slot, at) = self.locate(cid)?;
+        self.slots.remove(&cid);
+
+        let removed = match slot {
+            Slot::Single(id) => {
+                let constraint = self.single.get_mut(&id)?.remove(at);
+                self.single_ids.get_mut(&id)?.remove(at);
+                if self.single[&id].is_empty() {
+                    self.single.remove(&id);
+                    self.single_ids.remove(&id);
+                }
+                Constraint::Single(constraint)
+            }
+            Slot::Double(pair) => {
+                let constraint = self.double.get_mut(&pair)?.remove(at);
+                self.double_ids.get_mut(&pair)?.remove(at);
+                if self.double[&pair].is_empty() {
+                    self.double.remove(&pair);
+                    self.double_ids.remove(&pair);
+                }
+                Constraint::Double(constraint)
+            }
+        };
+
+        Some(removed)
+    }
+
+    /// replace without recording
+    fn swap(&mut self, cid: ConstraintId, constraint: Constraint) -> Option<Constraint> {
+        let (slot, at) = self.locate(cid)?;
+        if slot != constraint.slot() {
+            let old = self.take(cid)?;
+            self.place(cid, constraint);
+            return Some(old);
+        }
+
+        let old = match (slot, constraint) {
+            (Slot::Single(id), Constraint::Single(c)) => {
+                Constraint::Single(std::mem::replace(&mut self.single.get_mut(&id)?[at], c))
+            }
+            (Slot::Double(pair), Constraint::Double(c)) => {
+                Constraint::Double(std::mem::replace(&mut self.double.get_mut(&pair)?[at], c))
+            }
+            _ => unreachable!("slot of the constraint is checked"),
+        };
+        Some(old)
     }
 
     pub fn check_single(
@@ -107,7 +482,7 @@ impl Model {
 
         if let Some(vec) = self.double.get(&(left_id, right_id)) {
             for c in vec.iter() {
-                match c.check(&left_value, &right_value) {
+                match c.check_wide(left_value, right_value) {
                     DoubleConstrainCheckResult::Ok => continue,
                     DoubleConstrainCheckResult::Err(e) => errors.push(e),
                 }
@@ -121,3 +496,149 @@ impl Model {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relation(left: usize, right: usize) -> Constraint {
+        Constraint::Double(DoubleConstrain::LinearRelation(Linear::new(
+            left,
+            right,
+            None,
+            None,
+            Compare::LT,
+        )))
+    }
+
+    #[test]
+    fn ids_stay_valid_across_removals_and_replacements() {
+        let mut model = Model::new();
+        let fixed = model.add_fixed(0, 3);
+        let lt = model.add(relation(0, 1)).unwrap();
+        let gt = model.add_linear(0, 1, None, None, Compare::GT);
+        assert_eq!(
+            (fixed, lt, gt),
+            (ConstraintId(0), ConstraintId(1), ConstraintId(2))
+        );
+
+        assert_eq!(model.remove(lt), Some(relation(0, 1)));
+        assert_eq!(model.remove(lt), None);
+        assert!(model.get(gt).is_some());
+        // removed ids are not handed out again
+        assert_eq!(model.add(relation(1, 2)).unwrap(), ConstraintId(3));
+
+        // a replacement may move the constraint to other variables
+        assert_eq!(
+            model.replace(fixed, relation(0, 2)),
+            Some(Constraint::Single(SingleConstrain::Fixed(Fixed::new(0, 3))))
+        );
+        assert_eq!(model.get(fixed), Some(relation(0, 2)));
+        assert!(model.single().is_empty());
+        assert_eq!(model.replace(lt, relation(0, 2)), None);
+    }
+
+    #[test]
+    fn reversed_relations_are_rejected() {
+        let mut model = Model::new();
+        let lt = model.add(relation(0, 1)).unwrap();
+        assert!(matches!(
+            model.add(relation(1, 0)),
+            Err(ModelErrors::ReversedRelation { left: 1, right: 0 })
+        ));
+        assert!(model.add_with_id(ConstraintId(5), relation(1, 1)).is_err());
+        assert!(matches!(
+            model.add_with_id(lt, relation(0, 2)),
+            Err(ModelErrors::TakenId(cid)) if cid == lt
+        ));
+        assert_eq!(model.replace(lt, relation(1, 0)), None);
+        assert_eq!(model.constraints(), vec![(lt, relation(0, 1))]);
+    }
+
+    #[test]
+    fn pop_reverts_the_edits_of_the_scope() {
+        let mut model = Model::new();
+        let kept = model.add_fixed(0, 1);
+        let replaced = model.add(relation(0, 1)).unwrap();
+        model.set_name(0, "x");
+
+        model.push();
+        let added = model.add_fixed(1, 2);
+        model.remove(kept);
+        model.replace(replaced, relation(0, 2));
+        model.set_name(0, "y");
+        model.remove_name(0);
+        model.set_name(2, "z");
+        assert!(model.pop());
+
+        assert_eq!(model.get(added), None);
+        assert_eq!(
+            model.constraints(),
+            vec![
+                (
+                    kept,
+                    Constraint::Single(SingleConstrain::Fixed(Fixed::new(0, 1)))
+                ),
+                (replaced, relation(0, 1)),
+            ]
+        );
+        assert_eq!(model.name(0), Some("x"));
+        assert_eq!(model.name(2), None);
+        assert_eq!(model.depth(), 0);
+    }
+
+    #[test]
+    fn pop_restores_bounds_and_history() {
+        let mut model = Model::new();
+        model
+            .add_boundary(
+                0,
+                Some(Limit {
+                    point: 10,
+                    equal: true,
+                }),
+                None,
+            )
+            .unwrap();
+        model.propagate().unwrap();
+        let bounds = model.bounds.clone();
+
+        model.push();
+        assert!(model.history.is_empty());
+        model.add_fixed(0, 4);
+        model.propagate().unwrap();
+        assert_ne!(model.bounds, bounds);
+        assert!(model.history.can_undo());
+        assert!(model.pop());
+
+        assert_eq!(model.bounds, bounds);
+        // the history from before the scope, the boundary can still be undone
+        assert!(model.history.can_undo());
+        assert!(!model.history.can_redo());
+        model.undo();
+        assert!(model.constraints().is_empty());
+    }
+
+    #[test]
+    fn scopes_nest() {
+        let mut model = Model::new();
+        assert!(!model.pop());
+
+        model.push();
+        let outer = model.add_fixed(0, 1);
+        model.push();
+        model.add_fixed(1, 2);
+        // undoing inside the scope is reverted as well
+        model.undo();
+        model.add_fixed(2, 3);
+        assert_eq!(model.depth(), 2);
+
+        assert!(model.pop());
+        assert_eq!(model.constraints().len(), 1);
+        assert!(model.get(outer).is_some());
+        assert!(model.pop());
+        assert!(model.constraints().is_empty());
+        assert!(!model.pop());
+        assert_eq!(model.depth(), 0);
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::{Assignment, CheckReport, Constraint, ConstraintId, Model, Soft, Violation};
+
+/// one constraint to evaluate
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    cid: ConstraintId,
+    constraint: Constraint,
+    /// constrained variables, the second one only for relations
+    vars: (usize, Option<usize>),
+    soft: Option<Soft>,
+}
+
+/// constraints of a `Model` flattened for checking many assignments, see [`Model::compile`].
+/// edits of the model after compiling are not seen by the plan
+#[derive(Debug, Clone)]
+pub struct Plan {
+    /// ordered by id
+    steps: Vec<Step>,
+    names: HashMap<usize, String>,
+}
+
+impl Plan {
+    pub fn new(model: &Model) -> Self {
+        let steps = model
+            .constraints()
+            .into_iter()
+            .map(|(cid, constraint)| Step {
+                cid,
+                constraint,
+                vars: match constraint {
+                    Constraint::Single(c) => (c.id(), None),
+                    Constraint::Double(c) => (c.ids().0, Some(c.ids().1)),
+                },
+                soft: model.soft_of(cid),
+            })
+            .collect();
+
+        Self {
+            steps,
+            names: model.names().clone(),
+        }
+    }
+
+    /// number of constraints
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// evaluate every constraint against `assignment`
+    pub fn check(&self, assignment: &Assignment) -> CheckReport {
+        let mut report = CheckReport::default();
+
+        for step in self.steps.iter() {
+            let (l, r) = step.vars;
+            let left = assignment.get(&l).copied();
+            let right = r.map(|r| assignment.get(&r).copied());
+
+            let values = match (step.constraint, left, right) {
+                (Constraint::Single(_), Some(v), None) => vec![(l, v)],
+                (Constraint::Double(_), Some(lv), Some(Some(rv))) => {
+                    vec![(l, lv), (r.expect("relations have two variables"), rv)]
+                }
+                _ => {
+                    report.missing.extend(
+                        [Some(l), r]
+                            .into_iter()
+                            .flatten()
+                            .filter(|id| !assignment.contains_key(id)),
+                    );
+                    continue;
+                }
+            };
+            report.checked += 1;
+
+            // decided on the wide slack, the formula of a relation may overflow an i64
+            let distance = step
+                .constraint
+                .distance(&values.iter().map(|v| v.1).collect::<Vec<_>>())
+                .expect("a value of every constrained variable");
+            if distance == 0 {
+                continue;
+            }
+
+            if let Some(soft) = step.soft {
+                let penalty = report.penalties.entry(soft.priority).or_default();
+                *penalty = penalty.saturating_add(soft.penalty(distance));
+                report.soft.push(Violation {
+                    cid: step.cid,
+                    constraint: step.constraint,
+                    values,
+                    distance,
+                    soft: Some(soft),
+                });
+                continue;
+            }
+
+            for (id, _) in values.iter() {
+                report
+                    .by_variable
+                    .entry(*id)
+                    .or_default()
+                    .push(report.violations.len());
+            }
+            report.score = report.score.saturating_add(distance);
+            report.violations.push(Violation {
+                cid: step.cid,
+                constraint: step.constraint,
+                values,
+                distance,
+                soft: None,
+            });
+        }
+
+        for id in report
+            .missing
+            .iter()
+            .chain(report.by_variable.keys())
+            .copied()
+            .chain(
+                report
+                    .soft
+                    .iter()
+                    .flat_map(|v| v.values.iter().map(|(id, _)| *id)),
+            )
+            .collect::<Vec<_>>()
+        {
+            if let Some(name) = self.names.get(&id) {
+                report.names.insert(id, name.clone());
+            }
+        }
+
+        report
+    }
+
+    /// report of every row, in the same order.
+    /// rows are checked on every core with the `parallel` feature
+    pub fn check_all(&self, rows: &[Assignment]) -> Vec<CheckReport> {
+        #[cfg(feature = "parallel")]
+        let reports = rows.par_iter().map(|row| self.check(row)).collect();
+        #[cfg(not(feature = "parallel"))]
+        let reports = rows.iter().map(|row| self.check(row)).collect();
+
+        reports
+    }
+}
+
+impl Model {
+    /// flatten the constraints for checking many assignments
+    pub fn compile(&self) -> Plan {
+        Plan::new(self)
+    }
+
+    /// evaluate every constraint against `assignment`
+    pub fn check(&self, assignment: &Assignment) -> CheckReport {
+        self.compile().check(assignment)
+    }
+
+    /// report of every row, see [`Plan::check_all`]
+    pub fn check_all(&self, rows: &[Assignment]) -> Vec<CheckReport> {
+        self.compile().check_all(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Compare;
+    use super::*;
+
+    #[test]
+    fn large_values_do_not_overflow() {
+        let mut model = Model::new();
+        let lte = model.add_linear(0, 1, Some(2), Some(1), Compare::LTE);
+        model.add_linear(0, 1, Some(2), None, Compare::GT);
+        let assignment = Assignment::from([(0, 9_000_000_000_000_000_000), (1, 1)]);
+
+        let report = model.check(&assignment);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].cid, lte);
+        assert_eq!(report.violations[0].distance, i64::MAX);
+        assert!(model
+            .check_double(0, 9_000_000_000_000_000_000, 1, 1)
+            .is_some());
+    }
+}
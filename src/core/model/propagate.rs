@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use super::{Compare, ConstraintId, DoubleConstrain, Model, SingleConstrain};
+
+/// rounds of tightening before propagation gives up on reaching a fixpoint
+const MAX_ROUNDS: usize = 1000;
+
+/// inclusive interval of an integer variable, `None` is unbounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Domain {
+    pub lo: Option<i64>,
+    pub hi: Option<i64>,
+}
+
+impl Domain {
+    pub fn new(lo: Option<i64>, hi: Option<i64>) -> Self {
+        Self { lo, hi }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!((self.lo, self.hi), (Some(lo), Some(hi)) if lo > hi)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.lo.is_none_or(|lo| lo <= value) && self.hi.is_none_or(|hi| value <= hi)
+    }
+
+    /// the only value of the domain
+    pub fn fixed(&self) -> Option<i64> {
+        match (self.lo, self.hi) {
+            (Some(lo), Some(hi)) if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+
+    /// raise the lower bound, returns whether it changed
+    pub fn raise(&mut self, lo: i64) -> bool {
+        if self.lo.is_some_and(|l| l >= lo) {
+            return false;
+        }
+        self.lo = Some(lo);
+        true
+    }
+
+    /// lower the upper bound, returns whether it changed
+    pub fn lower(&mut self, hi: i64) -> bool {
+        if self.hi.is_some_and(|h| h <= hi) {
+            return false;
+        }
+        self.hi = Some(hi);
+        true
+    }
+
+    pub fn intersect(&self, other: &Domain) -> Domain {
+        let mut domain = *self;
+        if let Some(lo) = other.lo {
+            domain.raise(lo);
+        }
+        if let Some(hi) = other.hi {
+            domain.lower(hi);
+        }
+        domain
+    }
+}
+
+/// no value is left for the variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Infeasible {
+    pub var: usize,
+}
+
+fn clamp(value: i128) -> i64 {
+    value.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+fn floor_div(n: i128, d: i128) -> i128 {
+    let q = n / d;
+    if n % d != 0 && ((n < 0) != (d < 0)) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+fn ceil_div(n: i128, d: i128) -> i128 {
+    let q = n / d;
+    if n % d != 0 && ((n < 0) == (d < 0)) {
+        q + 1
+    } else {
+        q
+    }
+}
+
+/// range of `mul * x + off`
+fn scaled(domain: &Domain, mul: i64, off: i64) -> (Option<i128>, Option<i128>) {
+    let at = |bound: Option<i64>| bound.map(|b| b as i128 * mul as i128 + off as i128);
+    match mul >= 0 {
+        true => (at(domain.lo), at(domain.hi)),
+        false => (at(domain.hi), at(domain.lo)),
+    }
+}
+
+/// tighten `left` and `right` by `mul * left + off <= right`, returns whether one changed
+fn less_eq(left: &mut Domain, right: &mut Domain, mul: i64, off: i64) -> bool {
+    let mut changed = false;
+    if let Some(lo) = scaled(left, mul, off).0 {
+        changed |= right.raise(clamp(lo));
+    }
+    if let Some(hi) = right.hi {
+        let limit = hi as i128 - off as i128;
+        changed |= match mul {
+            0 => false,
+            m if m > 0 => left.lower(clamp(floor_div(limit, m as i128))),
+            m => left.raise(clamp(ceil_div(limit, m as i128))),
+        };
+    }
+    changed
+}
+
+/// tighten `left` and `right` by `mul * left + off >= right`, returns whether one changed
+fn greater_eq(left: &mut Domain, right: &mut Domain, mul: i64, off: i64) -> bool {
+    let mut changed = false;
+    if let Some(hi) = scaled(left, mul, off).1 {
+        changed |= right.lower(clamp(hi));
+    }
+    if let Some(lo) = right.lo {
+        let limit = lo as i128 - off as i128;
+        changed |= match mul {
+            0 => false,
+            m if m > 0 => left.raise(clamp(ceil_div(limit, m as i128))),
+            m => left.lower(clamp(floor_div(limit, m as i128))),
+        };
+    }
+    changed
+}
+
+impl Model {
+    /// domains of every variable implied by the hard constraints, tightened from `start`.
+    /// propagation is sound but not complete, an empty domain proves infeasibility while
+    /// nonempty domains may still contain no solution
+    pub fn propagated(
+        &self,
+        start: &HashMap<usize, Domain>,
+    ) -> Result<HashMap<usize, Domain>, Infeasible> {
+        let mut domains: HashMap<usize, Domain> = self
+            .variables()
+            .into_iter()
+            .map(|id| (id, Domain::default()))
+            .collect();
+        for (id, domain) in start.iter() {
+            let d = domains.entry(*id).or_default();
+            *d = d.intersect(domain);
+        }
+
+        // soft constraints may be violated, they do not narrow anything
+        for (id, constraints) in self.single.iter() {
+            let domain = domains.entry(*id).or_default();
+            for (c, cid) in constraints.iter().zip(self.single_ids[id].iter()) {
+                if self.is_soft(*cid) {
+                    continue;
+                }
+                match c {
+                    SingleConstrain::Boundary(b) => {
+                        if let Some(bot) = b.bot {
+                            domain
+                                .raise(bot.limit.point.saturating_add(i64::from(!bot.limit.equal)));
+                        }
+                        if let Some(top) = b.top {
+                            domain
+                                .lower(top.limit.point.saturating_sub(i64::from(!top.limit.equal)));
+                        }
+                    }
+                    SingleConstrain::Fixed(f) => {
+                        domain.raise(f.value);
+                        domain.lower(f.value);
+                    }
+                }
+            }
+        }
+        let check =
+            |domains: &HashMap<usize, Domain>| match domains.iter().find(|(_, d)| d.is_empty()) {
+                Some((var, _)) => Err(Infeasible { var: *var }),
+                None => Ok(()),
+            };
+        check(&domains)?;
+
+        for _ in 0..MAX_ROUNDS {
+            let mut changed = false;
+            for ((l, r), constraints) in self.double.iter() {
+                for (c, cid) in constraints.iter().zip(self.double_ids[&(*l, *r)].iter()) {
+                    if self.is_soft(*cid) {
+                        continue;
+                    }
+                    let DoubleConstrain::LinearRelation(linear) = c;
+                    let (mut left, mut right) = (domains[l], domains[r]);
+                    let (mul, off) = (linear.mul().unwrap_or(1), linear.off().unwrap_or(0));
+
+                    changed |= match linear.cmp() {
+                        Compare::LTE => less_eq(&mut left, &mut right, mul, off),
+                        Compare::LT => less_eq(&mut left, &mut right, mul, off.saturating_add(1)),
+                        Compare::GTE => greater_eq(&mut left, &mut right, mul, off),
+                        Compare::GT => {
+                            greater_eq(&mut left, &mut right, mul, off.saturating_sub(1))
+                        }
+                        Compare::EQ => {
+                            less_eq(&mut left, &mut right, mul, off)
+                                | greater_eq(&mut left, &mut right, mul, off)
+                        }
+                    };
+
+                    if left.is_empty() {
+                        return Err(Infeasible { var: *l });
+                    }
+                    if right.is_empty() {
+                        return Err(Infeasible { var: *r });
+                    }
+                    domains.insert(*l, left);
+                    domains.insert(*r, right);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        Ok(domains)
+    }
+
+    /// hard relations without a multiplier that contradict each other whatever the domains,
+    /// as `x < y` and `y < x`. propagation only finds these once the domains are bounded, one
+    /// step at a time
+    pub fn cyclic(&self) -> Result<(), Infeasible> {
+        // `a - b <= w` is an edge from b to a, a cycle of negative weight can not hold
+        let mut edges: Vec<(usize, usize, i128)> = Vec::new();
+        for ((l, r), constraints) in self.double.iter() {
+            for (c, cid) in constraints.iter().zip(self.double_ids[&(*l, *r)].iter()) {
+                let DoubleConstrain::LinearRelation(linear) = c;
+                if self.is_soft(*cid) || linear.mul().is_some_and(|m| m != 1) {
+                    continue;
+                }
+                // left + off cmp right
+                let off = linear.off().unwrap_or(0) as i128;
+                match linear.cmp() {
+                    Compare::LTE => edges.push((*r, *l, -off)),
+                    Compare::LT => edges.push((*r, *l, -off - 1)),
+                    Compare::GTE => edges.push((*l, *r, off)),
+                    Compare::GT => edges.push((*l, *r, off - 1)),
+                    Compare::EQ => {
+                        edges.push((*r, *l, -off));
+                        edges.push((*l, *r, off));
+                    }
+                }
+            }
+        }
+
+        if edges.is_empty() {
+            return Ok(());
+        }
+
+        // bellman-ford from a virtual source reaching every variable with weight 0
+        let mut dist: HashMap<usize, i128> = HashMap::new();
+        for (from, to, _) in edges.iter() {
+            dist.insert(*from, 0);
+            dist.insert(*to, 0);
+        }
+        for _ in 0..dist.len() {
+            let mut relaxed = false;
+            for (from, to, weight) in edges.iter() {
+                let via = dist[from] + weight;
+                if via < dist[to] {
+                    dist.insert(*to, via);
+                    relaxed = true;
+                }
+            }
+            if !relaxed {
+                return Ok(());
+            }
+        }
+        // still relaxing after as many rounds as variables
+        let var = edges
+            .iter()
+            .find(|(from, to, weight)| dist[from] + weight < dist[to])
+            .map_or(0, |(_, to, _)| *to);
+        Err(Infeasible { var })
+    }
+
+    /// propagate the constraints and keep the result in `bounds`
+    pub fn propagate(&mut self) -> Result<&HashMap<usize, Domain>, Infeasible> {
+        self.cyclic()?;
+        self.bounds = self.propagated(&HashMap::new())?;
+        Ok(&self.bounds)
+    }
+
+    /// whether propagation or a cycle of relations proves the hard constraints contradictory
+    fn contradicts(&self) -> bool {
+        self.cyclic().is_err() || self.propagated(&HashMap::new()).is_err()
+    }
+
+    /// hard constraints that propagation proves contradictory, none of them can be left out.
+    /// `None` if propagation finds no contradiction
+    pub fn conflict(&self) -> Option<Vec<ConstraintId>> {
+        if !self.contradicts() {
+            return None;
+        }
+        let hard: Vec<_> = self
+            .constraints()
+            .into_iter()
+            .filter(|(cid, _)| !self.is_soft(*cid))
+            .collect();
+
+        // drop every constraint the contradiction holds without
+        let mut needed = vec![true; hard.len()];
+        for i in 0..hard.len() {
+            needed[i] = false;
+            let mut rest = Model::new();
+            for ((_, c), _) in hard.iter().zip(needed.iter()).filter(|(_, n)| **n) {
+                rest.insert(*c);
+            }
+            needed[i] = !rest.contradicts();
+        }
+
+        Some(
+            hard.into_iter()
+                .zip(needed)
+                .filter_map(|((cid, _), n)| n.then_some(cid))
+                .collect(),
+        )
+    }
+
+    /// propagated domain of a variable, unbounded before [`Model::propagate`]
+    pub fn bounds_of(&self, id: usize) -> Domain {
+        self.bounds.get(&id).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_are_conflicts() {
+        let mut model = Model::new();
+        let lt = model.add_linear(0, 1, None, None, Compare::LT);
+        model.add_linear(1, 2, None, None, Compare::LT);
+        let gt = model.add_linear(0, 1, None, None, Compare::GT);
+        assert!(model.propagated(&HashMap::new()).is_ok());
+        assert!(model.cyclic().is_err());
+        assert!(model.propagate().is_err());
+        assert_eq!(model.conflict(), Some(vec![lt, gt]));
+
+        // a multiplier breaks the cycle, 2 * x < y < x holds for negative values
+        let mut model = Model::new();
+        model.add_linear(0, 1, Some(2), None, Compare::LT);
+        model.add_linear(0, 1, None, None, Compare::GT);
+        assert!(model.cyclic().is_ok());
+        assert_eq!(model.conflict(), None);
+    }
+}
@@ -0,0 +1,57 @@
+use super::{Assignment, Constraint, DoubleConstrain, Model, SingleConstrain};
+
+impl Constraint {
+    /// margin by which `values` satisfy the constraint, negative by how far they violate it.
+    /// `values` are the ones of the constrained variables, ( left, right ) for relations,
+    /// `None` if there are more or fewer
+    pub fn slack(&self, values: &[i64]) -> Option<i64> {
+        match (self, values) {
+            (Constraint::Single(SingleConstrain::Boundary(b)), [v]) => Some(b.slack(*v)),
+            (Constraint::Single(SingleConstrain::Fixed(f)), [v]) => Some(f.slack(*v)),
+            (Constraint::Double(DoubleConstrain::LinearRelation(l)), [left, right]) => {
+                Some(l.slack(*left, *right))
+            }
+            _ => None,
+        }
+    }
+
+    /// how far `values` are from satisfying the constraint, 0 if they do. `None` as for
+    /// [`Constraint::slack`]
+    pub fn distance(&self, values: &[i64]) -> Option<i64> {
+        Some(self.slack(values)?.saturating_neg().max(0))
+    }
+}
+
+impl Model {
+    /// sum of the distances of every violated hard constraint, constraints of variables without a
+    /// value are skipped
+    pub fn score(&self, assignment: &Assignment) -> i64 {
+        self.check(assignment).score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Compare, Fixed, Linear};
+    use super::*;
+
+    #[test]
+    fn slack_needs_a_value_per_variable() {
+        let fixed = Constraint::Single(SingleConstrain::Fixed(Fixed::new(0, 4)));
+        assert_eq!(fixed.slack(&[6]), Some(-2));
+        assert_eq!(fixed.distance(&[6]), Some(2));
+        assert_eq!(fixed.slack(&[]), None);
+        assert_eq!(fixed.slack(&[4, 4]), None);
+
+        let relation = Constraint::Double(DoubleConstrain::LinearRelation(Linear::new(
+            0,
+            1,
+            None,
+            None,
+            Compare::LT,
+        )));
+        assert_eq!(relation.slack(&[1, 5]), Some(3));
+        assert_eq!(relation.distance(&[1, 5]), Some(0));
+        assert_eq!(relation.distance(&[1]), None);
+    }
+}
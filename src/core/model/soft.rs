@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Command, ConstraintId, Model};
+
+fn default_weight() -> i64 {
+    1
+}
+
+/// marks a constraint as preferred instead of required, see [`Model::set_soft`].
+/// a violated soft constraint costs `weight` times its distance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Soft {
+    #[serde(default = "default_weight")]
+    pub weight: i64,
+    /// penalties of higher levels are minimized first, whatever the lower ones cost
+    #[serde(default)]
+    pub priority: u32,
+}
+
+impl Default for Soft {
+    fn default() -> Self {
+        Self {
+            weight: default_weight(),
+            priority: 0,
+        }
+    }
+}
+
+impl Soft {
+    pub fn new(weight: i64, priority: u32) -> Self {
+        Self { weight, priority }
+    }
+
+    /// cost of being `distance` away from satisfying the constraint
+    pub fn penalty(&self, distance: i64) -> i64 {
+        self.weight.saturating_mul(distance)
+    }
+}
+
+impl Model {
+    /// make the constraint soft, returns false if `cid` is unknown or the weight is negative
+    pub fn set_soft(&mut self, cid: ConstraintId, soft: Soft) -> bool {
+        soft.weight >= 0 && self.mark(cid, Some(soft)).is_some()
+    }
+
+    /// make the constraint required again, returns its soft mark
+    pub fn set_hard(&mut self, cid: ConstraintId) -> Option<Soft> {
+        self.mark(cid, None).flatten()
+    }
+
+    /// marks of the soft constraints, including those of removed ones
+    pub fn soft(&self) -> &HashMap<ConstraintId, Soft> {
+        &self.soft
+    }
+
+    pub fn soft_of(&self, cid: ConstraintId) -> Option<Soft> {
+        self.soft.get(&cid).copied()
+    }
+
+    pub fn is_soft(&self, cid: ConstraintId) -> bool {
+        self.soft.contains_key(&cid)
+    }
+
+    /// set or clear the mark of `cid`, returns the old one. `None` if `cid` is unknown
+    fn mark(&mut self, cid: ConstraintId, soft: Option<Soft>) -> Option<Option<Soft>> {
+        let constraint = self.get(cid)?;
+        let old = match soft {
+            Some(soft) => self.soft.insert(cid, soft),
+            None => self.soft.remove(&cid),
+        };
+        if old != soft {
+            self.record(Command::Soften {
+                cid,
+                constraint,
+                old,
+                new: soft,
+            });
+        }
+        Some(old)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Compare;
+    use super::*;
+
+    #[test]
+    fn negative_weights_are_rejected() {
+        let mut model = Model::new();
+        let cid = model.add_linear(0, 1, None, None, Compare::LT);
+        assert!(!model.set_soft(cid, Soft::new(-1, 0)));
+        assert!(!model.is_soft(cid));
+        assert!(model.set_soft(cid, Soft::new(0, 0)));
+        assert_eq!(model.soft().len(), 1);
+        // marks are edits like any other
+        model.undo();
+        assert!(model.soft().is_empty());
+        model.redo();
+        assert_eq!(model.soft_of(cid), Some(Soft::new(0, 0)));
+
+        let src = toml::to_string(&model).unwrap();
+        assert!(toml::from_str::<Model>(&src).is_ok());
+        let src = src.replace("weight = 0", "weight = -2");
+        assert!(toml::from_str::<Model>(&src).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeriesError {
+    /// the largest change of a `Rate` is below zero
+    NegativeRate { var: usize, max: i64 },
+}
+
+impl Display for SeriesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeriesError::NegativeRate { var, max } => {
+                write!(f, "the rate of #{} has a negative maximum {}", var, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SeriesError {}
@@ -0,0 +1,313 @@
+//! constraints over variables observed over time
+//!
+//! a [`Series`] holds one assignment per step. every sample is checked against the `Model`,
+//! and the [`Temporal`] rules are checked over consecutive samples.
+
+mod errors;
+
+use std::fmt::Display;
+
+use super::constraints::BoundaryCheckResult;
+use super::model::{Assignment, Boundary, CheckReport, Constraint, Model, SingleConstrain};
+
+pub use self::errors::SeriesError;
+
+/// samples of the variables, one assignment per step
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Series {
+    pub samples: Vec<Assignment>,
+}
+
+impl Series {
+    pub fn new(samples: Vec<Assignment>) -> Self {
+        Self { samples }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn push(&mut self, sample: Assignment) {
+        self.samples.push(sample);
+    }
+
+    /// value of `var` at every step, `None` where it was not observed
+    pub fn values(&self, var: usize) -> impl Iterator<Item = Option<i64>> + '_ {
+        self.samples.iter().map(move |s| s.get(&var).copied())
+    }
+}
+
+/// rule over consecutive samples of a variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Temporal {
+    /// `| x[t] - x[t - 1] | <= max` between every two observed steps in a row. a negative
+    /// `max` is an error
+    Rate { var: usize, max: i64 },
+    /// once within `boundary`, the variable stays within it for at least `samples` steps.
+    /// stretches cut short by the start or end of the series or by a missing value are not judged
+    Dwell {
+        boundary: Boundary<i64>,
+        samples: usize,
+    },
+}
+
+impl Temporal {
+    pub fn var(&self) -> usize {
+        match self {
+            Temporal::Rate { var, .. } => *var,
+            Temporal::Dwell { boundary, .. } => boundary.id(),
+        }
+    }
+
+    /// formula of the rule using the names of the variables of `model`
+    pub fn describe(&self, model: &Model) -> String {
+        match self {
+            Temporal::Rate { var, max } => {
+                let name = match model.name(*var) {
+                    Some(name) => name.to_string(),
+                    None => format!("#{}", var),
+                };
+                format!("| {0}[t] - {0}[t-1] | <= {1}", name, max)
+            }
+            Temporal::Dwell { boundary, samples } => format!(
+                "{} for at least {} samples",
+                model.describe(&Constraint::Single(SingleConstrain::Boundary(*boundary))),
+                samples
+            ),
+        }
+    }
+
+    /// every violation of the rule in `series`, ordered by step
+    pub fn check(&self, series: &Series) -> Result<Vec<SeriesViolation>, SeriesError> {
+        let values: Vec<_> = series.values(self.var()).collect();
+        let mut violations = Vec::new();
+
+        match self {
+            Temporal::Rate { var, max } if *max < 0 => {
+                return Err(SeriesError::NegativeRate {
+                    var: *var,
+                    max: *max,
+                })
+            }
+            Temporal::Rate { max, .. } => {
+                for (step, pair) in values.windows(2).enumerate() {
+                    let [Some(before), Some(after)] = pair else {
+                        continue;
+                    };
+                    let change = after.abs_diff(*before);
+                    if change > *max as u64 {
+                        violations.push(SeriesViolation {
+                            from: step,
+                            to: step + 1,
+                            distance: (change - *max as u64).min(i64::MAX as u64) as i64,
+                        });
+                    }
+                }
+            }
+            Temporal::Dwell { boundary, samples } => {
+                // ( first step of the current stretch within the boundary, whether the step before
+                // was observed outside of it )
+                let mut start = None;
+                for (step, value) in values.iter().enumerate() {
+                    let within =
+                        value.is_some_and(|v| matches!(boundary.is_in(v), BoundaryCheckResult::Ok));
+                    match (within, start) {
+                        (true, None) => {
+                            let entered = step > 0 && values[step - 1].is_some();
+                            start = Some((step, entered));
+                        }
+                        (false, Some((from, entered))) => {
+                            start = None;
+                            let run = step - from;
+                            // a missing value on either side leaves the length of the stretch
+                            // unknown
+                            if entered && value.is_some() && run < *samples {
+                                violations.push(SeriesViolation {
+                                    from,
+                                    to: step - 1,
+                                    distance: (*samples - run) as i64,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+}
+
+/// broken rule between the steps `from` and `to`, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesViolation {
+    pub from: usize,
+    pub to: usize,
+    /// excess change for `Rate`, missing samples for `Dwell`
+    pub distance: i64,
+}
+
+/// result of [`check`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeriesReport {
+    pub samples: usize,
+    /// ( step, report ) of the samples violating a constraint of the model
+    pub steps: Vec<(usize, CheckReport)>,
+    /// ( index of the rule, violation ), ordered by rule and step
+    pub violations: Vec<(usize, SeriesViolation)>,
+    /// formulas of the rules, by index in the checked rules
+    pub rules: Vec<String>,
+}
+
+impl SeriesReport {
+    /// no sample violates a hard constraint and no rule is broken,
+    /// unobserved variables are not an error
+    pub fn is_ok(&self) -> bool {
+        self.steps.iter().all(|(_, r)| r.violations.is_empty()) && self.violations.is_empty()
+    }
+}
+
+impl Display for SeriesReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} samples, {} failing the model, {} rules checked, broken {} times",
+            self.samples,
+            self.steps.len(),
+            self.rules.len(),
+            self.violations.len()
+        )?;
+        for (step, report) in self.steps.iter() {
+            for v in report.violations.iter().chain(report.soft.iter()) {
+                write!(f, "\nstep {}: {}", step, report.line(v))?;
+            }
+        }
+        for (rule, v) in self.violations.iter() {
+            write!(
+                f,
+                "\nsteps {} to {}: {}, off by {}",
+                v.from, v.to, self.rules[*rule], v.distance
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// check every sample of `series` against `model` and every rule over the samples
+pub fn check(
+    model: &Model,
+    rules: &[Temporal],
+    series: &Series,
+) -> Result<SeriesReport, SeriesError> {
+    let steps = model
+        .check_all(&series.samples)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, r)| !r.violations.is_empty() || !r.soft.is_empty())
+        .collect();
+
+    let mut violations = Vec::new();
+    for (i, rule) in rules.iter().enumerate() {
+        violations.extend(rule.check(series)?.into_iter().map(|v| (i, v)));
+    }
+
+    Ok(SeriesReport {
+        samples: series.len(),
+        steps,
+        violations,
+        rules: rules.iter().map(|r| r.describe(model)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::Limit;
+    use super::*;
+
+    fn series(values: &[i64]) -> Series {
+        Series::new(values.iter().map(|v| Assignment::from([(0, *v)])).collect())
+    }
+
+    #[test]
+    fn rates_limit_the_change() {
+        let rule = Temporal::Rate { var: 0, max: 2 };
+        let violations = rule.check(&series(&[0, 2, 7, 6])).unwrap();
+        assert_eq!(
+            violations,
+            vec![SeriesViolation {
+                from: 1,
+                to: 2,
+                distance: 3
+            }]
+        );
+    }
+
+    /// `0 <= x <= 10` for at least 3 samples
+    fn dwell() -> Temporal {
+        let bot = Limit {
+            point: 0,
+            equal: true,
+        };
+        let top = Limit {
+            point: 10,
+            equal: true,
+        };
+        Temporal::Dwell {
+            boundary: Boundary::create(0, Some(top), Some(bot)).unwrap(),
+            samples: 3,
+        }
+    }
+
+    fn with_gaps(values: &[Option<i64>]) -> Series {
+        Series::new(
+            values
+                .iter()
+                .map(|v| v.map(|v| Assignment::from([(0, v)])).unwrap_or_default())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn short_stretches_break_dwell() {
+        let violations = dwell()
+            .check(&series(&[99, 5, 5, 99, 5, 5, 5, 99]))
+            .unwrap();
+        assert_eq!(
+            violations,
+            vec![SeriesViolation {
+                from: 1,
+                to: 2,
+                distance: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn stretches_of_unknown_length_are_not_judged() {
+        let rule = dwell();
+        // cut short by the start and the end of the series
+        assert!(rule.check(&series(&[5, 5, 99])).unwrap().is_empty());
+        assert!(rule.check(&series(&[99, 5, 5])).unwrap().is_empty());
+        // cut short by a missing value on either side
+        let gaps = [None, Some(5), Some(5), Some(99)];
+        assert!(rule.check(&with_gaps(&gaps)).unwrap().is_empty());
+        let gaps = [Some(99), Some(5), Some(5), None, Some(99)];
+        assert!(rule.check(&with_gaps(&gaps)).unwrap().is_empty());
+        // a gap outside of the boundary does not hide the start of the next stretch
+        let gaps = [None, Some(99), Some(5), Some(99)];
+        assert_eq!(rule.check(&with_gaps(&gaps)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn negative_rates_are_rejected() {
+        let rule = Temporal::Rate { var: 0, max: -2 };
+        let err = SeriesError::NegativeRate { var: 0, max: -2 };
+        assert_eq!(rule.check(&series(&[0, 1])), Err(err.clone()));
+        assert_eq!(check(&Model::new(), &[rule], &series(&[0, 1])), Err(err));
+    }
+}
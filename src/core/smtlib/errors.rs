@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+use super::super::constraints::BoundaryError;
+
+#[derive(Debug)]
+pub enum SmtErrorKind {
+    /// unbalanced parentheses, unterminated literals, ..
+    Syntax(String),
+    UnknownSymbol(String),
+    /// a command or term outside of the subset this crate emits
+    Unsupported(String),
+    Boundary(BoundaryError<i64>),
+}
+
+#[derive(Debug)]
+pub struct SmtError {
+    /// 1-indexed line of the offending expression
+    pub line: usize,
+    pub kind: SmtErrorKind,
+}
+
+impl SmtError {
+    pub fn new(line: usize, kind: SmtErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl Display for SmtErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmtErrorKind::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            SmtErrorKind::UnknownSymbol(s) => write!(f, "undeclared symbol `{}`", s),
+            SmtErrorKind::Unsupported(term) => write!(f, "unsupported: {}", term),
+            SmtErrorKind::Boundary(e) => write!(f, "invalid bounds: {}", e),
+        }
+    }
+}
+
+impl Display for SmtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for SmtError {}
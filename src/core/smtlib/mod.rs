@@ -0,0 +1,243 @@
+//! SMT-LIB2 scripts ( QF_LIA / QF_LRA ) for cross-checking with external solvers
+//!
+//! every constraint becomes one `assert`, variables are declared as `v<id>` or by their name.
+//! names are mapped back to ids with `(set-info :abstaction-ids ((x 0) ..))` so that
+//! [`read`] restores the model written by [`write`]. a boundary without limits is written
+//! as `(! true :abstaction-boundary x)`.
+
+mod errors;
+mod parser;
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::constraints::{DoubleConstrain, Limit, SingleConstrain};
+use super::model::Model;
+
+pub use self::errors::{SmtError, SmtErrorKind};
+pub use self::parser::read;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Logic {
+    /// linear integer arithmetic, exact for `i64` models
+    QfLia,
+    /// linear real arithmetic, a relaxation of the model
+    QfLra,
+}
+
+impl Logic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Logic::QfLia => "QF_LIA",
+            Logic::QfLra => "QF_LRA",
+        }
+    }
+
+    pub fn sort(&self) -> &'static str {
+        match self {
+            Logic::QfLia => "Int",
+            Logic::QfLra => "Real",
+        }
+    }
+}
+
+const RESERVED: &[&str] = &[
+    "and", "or", "not", "xor", "ite", "let", "true", "false", "assert", "distinct", "forall",
+    "exists", "par", "as", "_", "!",
+];
+
+/// symbol for a variable name, quoted when it is no simple symbol.
+/// names looking like the `v<id>` symbols are not used
+fn name_symbol(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    let simple = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        && !RESERVED.contains(&name);
+
+    if parser::id_symbol(name).is_some() {
+        None
+    } else if simple {
+        Some(name.to_string())
+    } else if !name.is_empty() && !name.contains(['|', '\\']) {
+        Some(format!("|{}|", name))
+    } else {
+        None
+    }
+}
+
+fn numeral(value: i64) -> String {
+    if value < 0 {
+        format!("(- {})", value.unsigned_abs())
+    } else {
+        value.to_string()
+    }
+}
+
+fn bound(symbol: &str, limit: &Limit<i64>, top: bool) -> String {
+    let op = match (top, limit.equal) {
+        (true, true) => "<=",
+        (true, false) => "<",
+        (false, true) => ">=",
+        (false, false) => ">",
+    };
+    format!("({} {} {})", op, symbol, numeral(limit.point))
+}
+
+/// translate `model` into an SMT-LIB2 script
+pub fn write(model: &Model, logic: Logic) -> String {
+    let vars = model.variables();
+
+    let mut used = HashSet::new();
+    let symbols: Vec<(usize, String)> = vars
+        .iter()
+        .map(|id| match model.name(*id).and_then(name_symbol) {
+            Some(symbol) if used.insert(symbol.clone()) => (*id, symbol),
+            _ => (*id, format!("v{}", id)),
+        })
+        .collect();
+    let symbol = |id: usize| {
+        symbols
+            .iter()
+            .find_map(|(i, s)| (*i == id).then_some(s.as_str()))
+            .expect("every variable has a symbol")
+    };
+
+    let mut out = String::new();
+    writeln!(out, "; generated by abstaction").unwrap();
+    writeln!(out, "(set-option :produce-models true)").unwrap();
+    writeln!(out, "(set-logic {})", logic.name()).unwrap();
+
+    let named: Vec<_> = symbols
+        .iter()
+        .filter(|(id, s)| *s != format!("v{}", id))
+        .map(|(id, s)| format!("({} {})", s, id))
+        .collect();
+    if !named.is_empty() {
+        writeln!(out, "(set-info :abstaction-ids ({}))", named.join(" ")).unwrap();
+    }
+
+    for (_, s) in symbols.iter() {
+        writeln!(out, "(declare-fun {} () {})", s, logic.sort()).unwrap();
+    }
+
+    for id in vars.iter() {
+        let Some(constraints) = model.single().get(id) else {
+            continue;
+        };
+        let x = symbol(*id);
+        for c in constraints.iter() {
+            let term = match c {
+                SingleConstrain::Fixed(f) => format!("(= {} {})", x, numeral(f.value)),
+                SingleConstrain::Boundary(b) => match (&b.bot, &b.top) {
+                    (Some(bot), Some(top)) => format!(
+                        "(and {} {})",
+                        bound(x, &bot.limit, false),
+                        bound(x, &top.limit, true)
+                    ),
+                    (Some(bot), None) => bound(x, &bot.limit, false),
+                    (None, Some(top)) => bound(x, &top.limit, true),
+                    // annotated so that it is read back as a boundary
+                    (None, None) => format!("(! true :abstaction-boundary {})", x),
+                },
+            };
+            writeln!(out, "(assert {})", term).unwrap();
+        }
+    }
+
+    let mut pairs: Vec<_> = model.double().keys().collect();
+    pairs.sort();
+    for pair in pairs {
+        for c in model.double()[pair].iter() {
+            let DoubleConstrain::LinearRelation(l) = c;
+
+            let mut left = symbol(l.left()).to_string();
+            if let Some(mul) = l.mul() {
+                left = format!("(* {} {})", numeral(mul), left);
+            }
+            if let Some(off) = l.off() {
+                left = format!("(+ {} {})", left, numeral(off));
+            }
+            writeln!(
+                out,
+                "(assert ({} {} {}))",
+                l.cmp().symbol(),
+                left,
+                symbol(l.right())
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(out, "(check-sat)").unwrap();
+    writeln!(out, "(get-model)").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::constraints::Compare;
+    use super::super::model::Constraint;
+    use super::*;
+
+    fn constraints(model: &Model) -> Vec<Constraint> {
+        model.constraints().into_iter().map(|(_, c)| c).collect()
+    }
+
+    fn round_trip(model: &Model) -> Model {
+        let src = write(model, Logic::QfLia);
+        read(&src).unwrap_or_else(|e| panic!("{}\n{}", e, src))
+    }
+
+    #[test]
+    fn empty_boundary_is_kept() {
+        let mut model = Model::new();
+        model.add_boundary(0, None, None).unwrap();
+        model.add_fixed(1, 4);
+
+        let read = round_trip(&model);
+        assert_eq!(constraints(&read), constraints(&model));
+    }
+
+    #[test]
+    fn extreme_values() {
+        let limit = |point, equal| Some(Limit { point, equal });
+        let mut model = Model::new();
+        model
+            .add_boundary(0, limit(i64::MAX, true), limit(i64::MIN, true))
+            .unwrap();
+        model.add_boundary(1, None, limit(i64::MIN, false)).unwrap();
+        model.add_fixed(2, i64::MIN);
+        model.add_fixed(3, i64::MAX);
+        model.add_linear(0, 1, Some(i64::MIN), Some(i64::MIN), Compare::LTE);
+        model.add_linear(2, 3, Some(i64::MAX), Some(i64::MAX), Compare::GT);
+
+        let read = round_trip(&model);
+        assert_eq!(constraints(&read), constraints(&model));
+    }
+
+    #[test]
+    fn every_compare() {
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        model.set_name(1, "y".to_string());
+        let compares = [
+            Compare::LT,
+            Compare::LTE,
+            Compare::GT,
+            Compare::GTE,
+            Compare::EQ,
+        ];
+        // in the order they are written, by pair of variables
+        for cmp in compares {
+            model.add_linear(0, 1, Some(-2), Some(3), cmp);
+        }
+        for cmp in compares {
+            model.add_linear(1, 2, None, None, cmp);
+        }
+
+        let read = round_trip(&model);
+        assert_eq!(constraints(&read), constraints(&model));
+        assert_eq!(read.name(0), Some("x"));
+        assert_eq!(read.name(2), None);
+    }
+}
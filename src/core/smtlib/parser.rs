@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use super::super::constraints::{Compare, Limit};
+use super::super::model::{Model, ModelErrors};
+use super::errors::{SmtError, SmtErrorKind};
+
+#[derive(Debug, Clone)]
+enum SExpr {
+    Atom(String, usize),
+    List(Vec<SExpr>, usize),
+}
+
+impl SExpr {
+    fn line(&self) -> usize {
+        match self {
+            SExpr::Atom(_, line) | SExpr::List(_, line) => *line,
+        }
+    }
+
+    fn atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(a, _) => Some(a),
+            SExpr::List(_, _) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(a, _) => write!(f, "{}", a),
+            SExpr::List(items, _) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn syntax(line: usize, msg: &str) -> SmtError {
+    SmtError::new(line, SmtErrorKind::Syntax(msg.to_string()))
+}
+
+fn unsupported(expr: &SExpr) -> SmtError {
+    SmtError::new(expr.line(), SmtErrorKind::Unsupported(expr.to_string()))
+}
+
+fn parse(src: &str) -> Result<Vec<SExpr>, SmtError> {
+    // open lists and the line they start on
+    let mut stack: Vec<(Vec<SExpr>, usize)> = vec![(Vec::new(), 1)];
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            ';' => while chars.next_if(|c| *c != '\n').is_some() {},
+            '(' => stack.push((Vec::new(), line)),
+            ')' => {
+                let (items, start) = stack.pop().expect("stack always holds the top level");
+                match stack.last_mut() {
+                    Some((parent, _)) => parent.push(SExpr::List(items, start)),
+                    None => return Err(syntax(line, "unexpected `)`")),
+                }
+            }
+            '|' | '"' => {
+                let start = line;
+                let mut atom = String::new();
+                loop {
+                    match chars.next() {
+                        Some(d) if d == c => break,
+                        Some(d) => {
+                            if d == '\n' {
+                                line += 1;
+                            }
+                            atom.push(d);
+                        }
+                        None => return Err(syntax(start, "unterminated literal")),
+                    }
+                }
+                let atom = if c == '"' {
+                    format!("\"{}\"", atom)
+                } else {
+                    atom
+                };
+                stack.last_mut().unwrap().0.push(SExpr::Atom(atom, start));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut atom = c.to_string();
+                while let Some(d) =
+                    chars.next_if(|d| !d.is_whitespace() && !matches!(d, '(' | ')' | ';' | '|'))
+                {
+                    atom.push(d);
+                }
+                stack.last_mut().unwrap().0.push(SExpr::Atom(atom, line));
+            }
+        }
+    }
+
+    if stack.len() > 1 {
+        return Err(syntax(stack.last().unwrap().1, "unclosed `(`"));
+    }
+    Ok(stack.pop().unwrap().0)
+}
+
+/// id of a `v<id>` symbol
+pub(super) fn id_symbol(symbol: &str) -> Option<usize> {
+    let digits = symbol.strip_prefix('v')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn flip(cmp: Compare) -> Compare {
+    match cmp {
+        Compare::LT => Compare::GT,
+        Compare::LTE => Compare::GTE,
+        Compare::GT => Compare::LT,
+        Compare::GTE => Compare::LTE,
+        Compare::EQ => Compare::EQ,
+    }
+}
+
+/// `left * mul + off`
+struct Affine {
+    var: usize,
+    mul: Option<i64>,
+    off: Option<i64>,
+}
+
+struct Reader {
+    model: Model,
+    symbols: HashMap<String, usize>,
+}
+
+impl Reader {
+    /// numeral atom, wide enough for the `9223372036854775808` of `(- 9223372036854775808)`
+    fn wide(expr: &SExpr) -> Option<i128> {
+        let a = expr.atom()?;
+        a.strip_suffix(".0").unwrap_or(a).parse().ok()
+    }
+
+    fn numeral(expr: &SExpr) -> Option<i64> {
+        let value = match expr {
+            SExpr::Atom(_, _) => Self::wide(expr)?,
+            SExpr::List(items, _) => match items.as_slice() {
+                [op, n] if op.atom() == Some("-") => -Self::wide(n)?,
+                _ => return None,
+            },
+        };
+        value.try_into().ok()
+    }
+
+    fn var(&self, expr: &SExpr) -> Result<Option<usize>, SmtError> {
+        let Some(symbol) = expr.atom() else {
+            return Ok(None);
+        };
+        if Self::numeral(expr).is_some() {
+            return Ok(None);
+        }
+        match self.symbols.get(symbol) {
+            Some(id) => Ok(Some(*id)),
+            None => Err(SmtError::new(
+                expr.line(),
+                SmtErrorKind::UnknownSymbol(symbol.to_string()),
+            )),
+        }
+    }
+
+    /// `x`, `(* m x)`, `(+ (* m x) c)` and the operands swapped
+    fn affine(&self, expr: &SExpr) -> Result<Option<Affine>, SmtError> {
+        if let Some(var) = self.var(expr)? {
+            return Ok(Some(Affine {
+                var,
+                mul: None,
+                off: None,
+            }));
+        }
+        let SExpr::List(items, _) = expr else {
+            return Ok(None);
+        };
+
+        match items.as_slice() {
+            [op, a, b] if op.atom() == Some("*") => {
+                let (m, x) = match Self::numeral(a) {
+                    Some(m) => (m, b),
+                    None => match Self::numeral(b) {
+                        Some(m) => (m, a),
+                        None => return Ok(None),
+                    },
+                };
+                Ok(self.var(x)?.map(|var| Affine {
+                    var,
+                    mul: Some(m),
+                    off: None,
+                }))
+            }
+            [op, a, b] if op.atom() == Some("+") => {
+                let (c, inner) = match Self::numeral(b) {
+                    Some(c) => (c, a),
+                    None => match Self::numeral(a) {
+                        Some(c) => (c, b),
+                        None => return Ok(None),
+                    },
+                };
+                Ok(self.affine(inner)?.and_then(|aff| match aff.off {
+                    Some(_) => None,
+                    None => Some(Affine {
+                        off: Some(c),
+                        ..aff
+                    }),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// comparison of a single variable with a constant, oriented as `x cmp c`
+    fn bound(&self, expr: &SExpr) -> Result<Option<(usize, Compare, i64)>, SmtError> {
+        let SExpr::List(items, _) = expr else {
+            return Ok(None);
+        };
+        let [op, a, b] = items.as_slice() else {
+            return Ok(None);
+        };
+        let Some(cmp) = op.atom().and_then(Compare::from_symbol) else {
+            return Ok(None);
+        };
+
+        if let (Some(x), Some(c)) = (self.var(a)?, Self::numeral(b)) {
+            return Ok(Some((x, cmp, c)));
+        }
+        if let (Some(c), Some(x)) = (Self::numeral(a), self.var(b)?) {
+            return Ok(Some((x, flip(cmp), c)));
+        }
+        Ok(None)
+    }
+
+    fn add_bounds(
+        &mut self,
+        expr: &SExpr,
+        id: usize,
+        bounds: &[(usize, Compare, i64)],
+    ) -> Result<(), SmtError> {
+        let mut top = None;
+        let mut bot = None;
+        for (_, cmp, point) in bounds {
+            let (slot, equal) = match cmp {
+                Compare::LT => (&mut top, false),
+                Compare::LTE => (&mut top, true),
+                Compare::GT => (&mut bot, false),
+                Compare::GTE => (&mut bot, true),
+                Compare::EQ => return Err(unsupported(expr)),
+            };
+            if slot.is_some() {
+                return Err(unsupported(expr));
+            }
+            *slot = Some(Limit {
+                point: *point,
+                equal,
+            });
+        }
+
+        self.model.add_boundary(id, top, bot).map_err(|e| match e {
+            ModelErrors::Boundary(e) => SmtError::new(expr.line(), SmtErrorKind::Boundary(e)),
+            ModelErrors::NotBoundary(_)
+            | ModelErrors::TakenId(_)
+            | ModelErrors::ReversedRelation { .. } => unreachable!("boundaries are only added"),
+        })?;
+        Ok(())
+    }
+
+    fn assert(&mut self, term: &SExpr) -> Result<(), SmtError> {
+        let SExpr::List(items, _) = term else {
+            return Err(unsupported(term));
+        };
+
+        // boundary without limits
+        if let [bang, t, key, x] = items.as_slice() {
+            if bang.atom() == Some("!")
+                && t.atom() == Some("true")
+                && key.atom() == Some(":abstaction-boundary")
+            {
+                let x = self.var(x)?.ok_or_else(|| unsupported(term))?;
+                return self.add_bounds(term, x, &[]);
+            }
+        }
+
+        if let [op, a, b] = items.as_slice() {
+            if op.atom() == Some("and") {
+                let (Some(a), Some(b)) = (self.bound(a)?, self.bound(b)?) else {
+                    return Err(unsupported(term));
+                };
+                if a.0 != b.0 {
+                    return Err(unsupported(term));
+                }
+                return self.add_bounds(term, a.0, &[a, b]);
+            }
+        }
+
+        if let Some((x, cmp, c)) = self.bound(term)? {
+            if cmp == Compare::EQ {
+                self.model.add_fixed(x, c);
+                return Ok(());
+            }
+            return self.add_bounds(term, x, &[(x, cmp, c)]);
+        }
+
+        let [op, a, b] = items.as_slice() else {
+            return Err(unsupported(term));
+        };
+        let Some(cmp) = op.atom().and_then(Compare::from_symbol) else {
+            return Err(unsupported(term));
+        };
+
+        let relation = match (self.affine(a)?, self.var(b)?) {
+            (Some(left), Some(right)) => Some((left, cmp, right)),
+            _ => match (self.var(a)?, self.affine(b)?) {
+                (Some(right), Some(left)) => Some((left, flip(cmp), right)),
+                _ => None,
+            },
+        };
+        match relation {
+            Some((left, cmp, right)) if left.var < right => {
+                self.model
+                    .add_linear(left.var, right, left.mul, left.off, cmp);
+                Ok(())
+            }
+            _ => Err(unsupported(term)),
+        }
+    }
+}
+
+/// read a script in the subset written by [`super::write`]
+pub fn read(src: &str) -> Result<Model, SmtError> {
+    let commands = parse(src)?;
+
+    let mut reader = Reader {
+        model: Model::new(),
+        symbols: HashMap::new(),
+    };
+
+    // ids have to be known before any constraint, undeclared names get fresh ids afterwards
+    let mut named: HashMap<String, usize> = HashMap::new();
+    let mut declared = Vec::new();
+    for cmd in commands.iter() {
+        let SExpr::List(items, _) = cmd else {
+            return Err(unsupported(cmd));
+        };
+        match items.first().and_then(|h| h.atom()) {
+            Some("set-info") => {
+                if let [_, key, SExpr::List(pairs, _)] = items.as_slice() {
+                    if key.atom() == Some(":abstaction-ids") {
+                        for pair in pairs {
+                            let SExpr::List(pair, _) = pair else {
+                                return Err(unsupported(cmd));
+                            };
+                            let [SExpr::Atom(name, _), id] = pair.as_slice() else {
+                                return Err(unsupported(cmd));
+                            };
+                            let id = id
+                                .atom()
+                                .and_then(|id| id.parse().ok())
+                                .ok_or_else(|| unsupported(cmd))?;
+                            named.insert(name.clone(), id);
+                        }
+                    }
+                }
+            }
+            Some("declare-fun") => match items.as_slice() {
+                [_, SExpr::Atom(name, _), SExpr::List(args, _), sort]
+                    if args.is_empty() && matches!(sort.atom(), Some("Int" | "Real")) =>
+                {
+                    declared.push(name.clone())
+                }
+                _ => return Err(unsupported(cmd)),
+            },
+            Some("declare-const") => match items.as_slice() {
+                [_, SExpr::Atom(name, _), sort] if matches!(sort.atom(), Some("Int" | "Real")) => {
+                    declared.push(name.clone())
+                }
+                _ => return Err(unsupported(cmd)),
+            },
+            _ => {}
+        }
+    }
+
+    for name in declared.iter() {
+        if let Some(id) = named.get(name).copied().or_else(|| id_symbol(name)) {
+            reader.symbols.insert(name.clone(), id);
+            if named.contains_key(name) {
+                reader.model.set_name(id, name.clone());
+            }
+        }
+    }
+    let mut next = reader.symbols.values().max().map_or(0, |m| m + 1);
+    for name in declared.iter() {
+        if !reader.symbols.contains_key(name) {
+            reader.symbols.insert(name.clone(), next);
+            reader.model.set_name(next, name.clone());
+            next += 1;
+        }
+    }
+
+    for cmd in commands.iter() {
+        let SExpr::List(items, _) = cmd else {
+            continue;
+        };
+        match items.first().and_then(|h| h.atom()) {
+            Some("assert") => match items.as_slice() {
+                [_, term] if term.atom() == Some("true") => {}
+                [_, term] => reader.assert(term)?,
+                _ => return Err(unsupported(cmd)),
+            },
+            Some(
+                "set-info" | "set-option" | "set-logic" | "declare-fun" | "declare-const"
+                | "check-sat" | "get-model" | "get-value" | "exit",
+            ) => {}
+            _ => return Err(unsupported(cmd)),
+        }
+    }
+
+    reader.model.history.clear();
+    Ok(reader.model)
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use super::super::model::{Assignment, Domain, Model};
+use super::super::statement::{StatementError, Sum};
+use super::soft::Penalties;
+use super::{search, NoSolution, Objective, Solution, NODE_LIMIT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Minimize,
+    Maximize,
+}
+
+/// `2 * x - y + 3`, the terms are ordered by variable
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinearExpr {
+    /// ( variable, coefficient ), without zero coefficients
+    pub terms: Vec<(usize, i64)>,
+    pub constant: i64,
+}
+
+impl LinearExpr {
+    /// parse sums of terms as `3`, `x`, `2 * x` or `x * 2`, variables by name or id ( `#3` )
+    pub fn parse(model: &Model, src: &str) -> Result<Self, StatementError> {
+        let sum = Sum::parse(src)?;
+        let mut coefficients: HashMap<usize, i64> = HashMap::new();
+        for (name, c) in sum.terms {
+            let id = model
+                .lookup(&name)
+                .ok_or(StatementError::UnknownVariable(name))?;
+            let coefficient = coefficients.entry(id).or_default();
+            *coefficient = coefficient.checked_add(c).ok_or(StatementError::Overflow)?;
+        }
+
+        let mut terms: Vec<_> = coefficients.into_iter().filter(|(_, c)| *c != 0).collect();
+        terms.sort();
+        Ok(Self {
+            terms,
+            constant: sum.constant,
+        })
+    }
+
+    /// least and greatest value of the expression within `domains`, `None` if unbounded
+    pub fn range(&self, domains: &HashMap<usize, Domain>) -> (Option<i128>, Option<i128>) {
+        let mut range = (Some(self.constant as i128), Some(self.constant as i128));
+        for (id, c) in self.terms.iter() {
+            let domain = domains.get(id).copied().unwrap_or_default();
+            let (least, greatest) = match *c > 0 {
+                true => (domain.lo, domain.hi),
+                false => (domain.hi, domain.lo),
+            };
+            let at = |v: i64| *c as i128 * v as i128;
+            range.0 = range.0.zip(least).map(|(sum, v)| sum + at(v));
+            range.1 = range.1.zip(greatest).map(|(sum, v)| sum + at(v));
+        }
+        range
+    }
+
+    /// value of the expression, `None` if a variable has no value
+    pub fn eval(&self, assignment: &Assignment) -> Option<i128> {
+        self.terms
+            .iter()
+            .try_fold(self.constant as i128, |sum, (id, c)| {
+                Some(sum + *c as i128 * *assignment.get(id)? as i128)
+            })
+    }
+}
+
+/// the expression first, then the penalties of soft constraints
+struct Optimum<'a> {
+    expr: &'a LinearExpr,
+    /// 1 to minimize, -1 to maximize
+    sign: i128,
+    penalties: Penalties,
+}
+
+impl Optimum<'_> {
+    fn coefficient(&self, var: usize) -> i128 {
+        self.expr
+            .terms
+            .iter()
+            .find_map(|(id, c)| (*id == var).then_some(*c as i128 * self.sign))
+            .unwrap_or(0)
+    }
+}
+
+impl Objective for Optimum<'_> {
+    fn bound(&self, domains: &HashMap<usize, Domain>) -> Vec<i64> {
+        let least = match self.sign > 0 {
+            true => self.expr.range(domains).0,
+            false => self.expr.range(domains).1.map(|g| -g),
+        };
+        let least = least.map_or(i64::MIN, |l| {
+            l.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        });
+
+        let mut cost = vec![least];
+        cost.extend(self.penalties.bound(domains));
+        cost
+    }
+
+    fn target(&self, var: usize, domain: &Domain) -> i64 {
+        let c = self.coefficient(var);
+        let (best, worst) = match c > 0 {
+            true => (domain.lo, domain.hi),
+            false => (domain.hi, domain.lo),
+        };
+        match c {
+            0 => self.penalties.target(var, domain),
+            _ => best.or(worst).unwrap_or(0),
+        }
+    }
+}
+
+impl Model {
+    /// assignment satisfying the hard constraints with the least or greatest value of `expr`,
+    /// ties are broken by the penalties of soft constraints ( see [`Model::solve`] ).
+    /// the solution is not `optimal` for unbounded goals
+    pub fn optimize(&self, expr: &LinearExpr, goal: Goal) -> Result<Solution, NoSolution> {
+        let objective = Optimum {
+            expr,
+            sign: match goal {
+                Goal::Minimize => 1,
+                Goal::Maximize => -1,
+            },
+            penalties: Penalties::new(self),
+        };
+        let mut start = HashMap::new();
+        // variables of the expression only are searched too
+        for (id, _) in expr.terms.iter() {
+            start.insert(*id, Domain::default());
+        }
+        search(self, start, &objective, NODE_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::model::{Compare, Limit};
+    use super::*;
+
+    fn limit(point: i64) -> Option<Limit<i64>> {
+        Some(Limit { point, equal: true })
+    }
+
+    /// `0 <= x <= 10`, `0 <= y <= 10` and `x + 2 <= y`
+    fn model() -> Model {
+        let mut model = Model::new();
+        model.add_boundary(0, limit(10), limit(0)).unwrap();
+        model.add_boundary(1, limit(10), limit(0)).unwrap();
+        model.add_linear(0, 1, None, Some(2), Compare::LTE);
+        model
+    }
+
+    #[test]
+    fn optimize_both_goals() {
+        let model = model();
+        let sum = LinearExpr {
+            terms: vec![(0, 1), (1, 1)],
+            constant: 1,
+        };
+        let best = model.optimize(&sum, Goal::Maximize).unwrap();
+        assert!(best.optimal);
+        assert_eq!(best.assignment, Assignment::from([(0, 8), (1, 10)]));
+        assert_eq!(sum.eval(&best.assignment), Some(19));
+
+        let difference = LinearExpr {
+            terms: vec![(0, 1), (1, -2)],
+            constant: 0,
+        };
+        let best = model.optimize(&difference, Goal::Minimize).unwrap();
+        assert_eq!(difference.eval(&best.assignment), Some(-20));
+    }
+
+    #[test]
+    fn range_within_domains() {
+        let expr = LinearExpr {
+            terms: vec![(0, 2), (1, -1)],
+            constant: 3,
+        };
+        let domains = HashMap::from([
+            (0, Domain::new(Some(0), Some(4))),
+            (1, Domain::new(Some(-1), None)),
+        ]);
+        // y has no top, so the expression has no least value
+        assert_eq!(expr.range(&domains), (None, Some(12)));
+        assert_eq!(expr.eval(&Assignment::from([(0, 1)])), None);
+
+        let mut model = model();
+        model.add_fixed(0, 11);
+        assert_eq!(
+            model.optimize(&expr, Goal::Minimize),
+            Err(NoSolution::Infeasible)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! branch and bound over the integer domains of a `Model`
+//!
+//! every node propagates the constraints ( see [`Model::propagated`] ), and is split on one
+//! variable into `x = t`, `x < t` and `x > t` where `t` is the value the objective prefers.
+//! costs are compared lexicographically, so objectives can rank several criteria. relations
+//! contradicting each other are found before branching ( see [`Model::cyclic`] ).
+
+mod linear;
+mod repair;
+mod soft;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use super::model::{Assignment, Domain, Model};
+
+pub use self::linear::{Goal, LinearExpr};
+pub use self::repair::{Norm, Repair};
+
+/// nodes visited before the search gives up on proving optimality
+pub const NODE_LIMIT: usize = 200_000;
+
+/// what the search minimizes
+pub trait Objective {
+    /// lower bound of the cost of every assignment within `domains`, exact once every domain
+    /// is a single value
+    fn bound(&self, domains: &HashMap<usize, Domain>) -> Vec<i64>;
+
+    /// value of `var` to try first
+    fn target(&self, var: usize, domain: &Domain) -> i64;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub assignment: Assignment,
+    pub cost: Vec<i64>,
+    /// false if the node limit was hit, a better solution may exist then
+    pub optimal: bool,
+}
+
+/// why a search ended without a solution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoSolution {
+    /// the hard constraints can not hold together
+    Infeasible,
+    /// the node limit was hit before a solution was found, one may still exist
+    LimitReached,
+}
+
+impl Display for NoSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoSolution::Infeasible => write!(f, "no solution exists"),
+            NoSolution::LimitReached => {
+                write!(
+                    f,
+                    "unknown, the search limit was reached before a solution was found"
+                )
+            }
+        }
+    }
+}
+
+/// `value` moved into `domain`
+pub fn clamp(value: i64, domain: &Domain) -> i64 {
+    let value = domain.lo.map_or(value, |lo| value.max(lo));
+    domain.hi.map_or(value, |hi| value.min(hi))
+}
+
+/// width of the domain, `u64::MAX` if unbounded
+fn width(domain: &Domain) -> u64 {
+    match (domain.lo, domain.hi) {
+        (Some(lo), Some(hi)) => hi.abs_diff(lo),
+        _ => u64::MAX,
+    }
+}
+
+/// best solution within `start`
+pub fn search(
+    model: &Model,
+    start: HashMap<usize, Domain>,
+    objective: &impl Objective,
+    limit: usize,
+) -> Result<Solution, NoSolution> {
+    // branching would never empty the domains of such a cycle
+    if model.cyclic().is_err() {
+        return Err(NoSolution::Infeasible);
+    }
+
+    let mut best: Option<(Assignment, Vec<i64>)> = None;
+    let mut stack = vec![start];
+    let mut visited = 0;
+
+    while let Some(node) = stack.pop() {
+        if visited >= limit {
+            return best
+                .map(|(assignment, cost)| Solution {
+                    assignment,
+                    cost,
+                    optimal: false,
+                })
+                .ok_or(NoSolution::LimitReached);
+        }
+        visited += 1;
+
+        let Ok(domains) = model.propagated(&node) else {
+            continue;
+        };
+        let bound = objective.bound(&domains);
+        if best.as_ref().is_some_and(|(_, cost)| bound >= *cost) {
+            continue;
+        }
+
+        // split the smallest open domain
+        let open = domains
+            .iter()
+            .filter(|(_, d)| d.fixed().is_none())
+            .min_by_key(|(id, d)| (width(d), **id));
+        let Some((var, domain)) = open else {
+            let assignment: Assignment = domains
+                .iter()
+                .filter_map(|(id, d)| Some((*id, d.fixed()?)))
+                .collect();
+            // soft violations are part of the cost
+            if model.check(&assignment).violations.is_empty() {
+                best = Some((assignment, bound));
+            }
+            continue;
+        };
+
+        let (var, domain) = (*var, *domain);
+        let target = clamp(objective.target(var, &domain), &domain);
+        let child = |d: Domain| {
+            let mut node = domains.clone();
+            node.insert(var, d);
+            node
+        };
+        // pushed in reverse, `x = t` is explored first
+        if let Some(above) = target.checked_add(1).filter(|a| domain.contains(*a)) {
+            stack.push(child(Domain::new(Some(above), domain.hi)));
+        }
+        if let Some(below) = target.checked_sub(1).filter(|b| domain.contains(*b)) {
+            stack.push(child(Domain::new(domain.lo, Some(below))));
+        }
+        stack.push(child(Domain::new(Some(target), Some(target))));
+    }
+
+    best.map(|(assignment, cost)| Solution {
+        assignment,
+        cost,
+        optimal: true,
+    })
+    .ok_or(NoSolution::Infeasible)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::Compare;
+    use super::*;
+
+    #[test]
+    fn contradicting_relations_end_the_search() {
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, None, Compare::LT);
+        model.add_linear(0, 1, None, None, Compare::GT);
+        assert_eq!(model.solve(), Err(NoSolution::Infeasible));
+
+        // through a third variable and with offsets
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, Some(2), Compare::LTE);
+        model.add_linear(1, 2, None, None, Compare::LTE);
+        model.add_linear(0, 2, None, Some(1), Compare::GTE);
+        assert_eq!(model.solve(), Err(NoSolution::Infeasible));
+    }
+
+    #[test]
+    fn relations_without_a_cycle_are_solved() {
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, Some(2), Compare::LTE);
+        model.add_linear(1, 2, None, None, Compare::LTE);
+        model.add_linear(0, 2, None, Some(2), Compare::GTE);
+        let solution = model.solve().unwrap();
+        assert!(model.check(&solution.assignment).violations.is_empty());
+        assert!(solution.optimal);
+    }
+
+    #[test]
+    fn the_limit_is_no_proof() {
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, None, Compare::LT);
+        let penalties = soft::Penalties::new(&model);
+        assert_eq!(
+            search(&model, HashMap::new(), &penalties, 1),
+            Err(NoSolution::LimitReached)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use super::super::model::{Assignment, Domain, Model};
+use super::{search, NoSolution, Objective, NODE_LIMIT};
+
+/// distance between assignments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Norm {
+    /// sum of the changes
+    L1,
+    /// largest change
+    LInf,
+}
+
+/// nearest feasible assignment, see [`Model::repair`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub assignment: Assignment,
+    /// distance to the given assignment
+    pub distance: i64,
+    /// ( id, old value, new value ) of the changed variables, ordered by id
+    pub changes: Vec<(usize, i64, i64)>,
+    /// false if the search was cut short, a closer assignment may exist then
+    pub optimal: bool,
+}
+
+struct Nearest<'a> {
+    from: &'a Assignment,
+    norm: Norm,
+}
+
+impl Objective for Nearest<'_> {
+    /// ( distance, number of changed variables )
+    fn bound(&self, domains: &HashMap<usize, Domain>) -> Vec<i64> {
+        let mut distance = 0i64;
+        let mut changed = 0;
+        for (id, domain) in domains.iter() {
+            let Some(value) = self.from.get(id) else {
+                continue;
+            };
+            let d = match (domain.lo, domain.hi) {
+                (Some(lo), _) if *value < lo => lo.abs_diff(*value),
+                (_, Some(hi)) if *value > hi => value.abs_diff(hi),
+                _ => 0,
+            }
+            .min(i64::MAX as u64) as i64;
+            if d > 0 {
+                changed += 1;
+            }
+            distance = match self.norm {
+                Norm::L1 => distance.saturating_add(d),
+                Norm::LInf => distance.max(d),
+            };
+        }
+        vec![distance, changed]
+    }
+
+    fn target(&self, var: usize, _: &Domain) -> i64 {
+        self.from.get(&var).copied().unwrap_or(0)
+    }
+}
+
+impl Model {
+    /// closest assignment to `from` satisfying every hard constraint, with the fewest changed
+    /// variables among the closest ones. variables without a value in `from` are chosen freely.
+    ///
+    /// the search is kept to a box around `from` whose radius is doubled while it holds no
+    /// feasible assignment. once the box holds one at least as close as its radius, nothing
+    /// outside of it can be closer under either norm.
+    pub fn repair(&self, from: &Assignment, norm: Norm) -> Result<Repair, NoSolution> {
+        let objective = Nearest { from, norm };
+        let mut radius: u64 = 1;
+        let solution = loop {
+            let start = from
+                .iter()
+                .map(|(id, value)| (*id, around(*value, radius)))
+                .collect();
+            match search(self, start, &objective, NODE_LIMIT) {
+                Ok(solution) if !solution.optimal || solution.cost[0] as u64 <= radius => {
+                    break solution
+                }
+                // a closer assignment may lie outside the box, but not beyond the one found
+                Ok(solution) => radius = solution.cost[0] as u64,
+                Err(NoSolution::Infeasible) if radius < u64::MAX => {
+                    radius = radius.saturating_mul(2)
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut changes: Vec<_> = from
+            .iter()
+            .filter_map(|(id, old)| {
+                let new = *solution.assignment.get(id)?;
+                (new != *old).then_some((*id, *old, new))
+            })
+            .collect();
+        changes.sort();
+
+        let mut assignment = from.clone();
+        assignment.extend(solution.assignment);
+
+        Ok(Repair {
+            assignment,
+            distance: solution.cost[0],
+            changes,
+            optimal: solution.optimal,
+        })
+    }
+}
+
+/// values at most `radius` away from `value`
+fn around(value: i64, radius: u64) -> Domain {
+    Domain::new(
+        value.checked_sub_unsigned(radius),
+        value.checked_add_unsigned(radius),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::model::Compare;
+    use super::*;
+
+    #[test]
+    fn repairs_are_found_beyond_the_first_box() {
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, Some(100), Compare::LTE);
+        let from = Assignment::from([(0, 0), (1, 0)]);
+
+        let repair = model.repair(&from, Norm::L1).unwrap();
+        assert_eq!(repair.distance, 100);
+        assert_eq!(repair.changes.len(), 1);
+        assert!(repair.optimal);
+
+        let repair = model.repair(&from, Norm::LInf).unwrap();
+        assert_eq!(repair.distance, 50);
+        assert_eq!(repair.changes.len(), 2);
+    }
+
+    #[test]
+    fn infeasible_models_are_not_repaired() {
+        let mut model = Model::new();
+        model.add_linear(0, 1, None, None, Compare::LT);
+        model.add_linear(0, 1, None, None, Compare::GT);
+        let from = Assignment::from([(0, 0), (1, 0)]);
+        assert_eq!(model.repair(&from, Norm::L1), Err(NoSolution::Infeasible));
+    }
+}
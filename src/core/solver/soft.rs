@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use super::super::model::{
+    Compare, Constraint, Domain, DoubleConstrain, Linear, Model, SingleConstrain, Soft,
+};
+use super::{clamp, search, NoSolution, Objective, Solution, NODE_LIMIT};
+
+/// values satisfying a constraint of a single variable
+fn satisfying(constraint: &SingleConstrain<i64>) -> Domain {
+    match constraint {
+        SingleConstrain::Boundary(b) => Domain::new(
+            b.bot
+                .map(|bot| bot.limit.point.saturating_add(i64::from(!bot.limit.equal))),
+            b.top
+                .map(|top| top.limit.point.saturating_sub(i64::from(!top.limit.equal))),
+        ),
+        SingleConstrain::Fixed(f) => Domain::new(Some(f.value), Some(f.value)),
+    }
+}
+
+/// least distance of the relation over the values of `left` and `right`
+fn least_linear(linear: &Linear<i64, i64>, left: &Domain, right: &Domain) -> i64 {
+    let (mul, off) = (
+        linear.mul().unwrap_or(1) as i128,
+        linear.off().unwrap_or(0) as i128,
+    );
+    let at = |bound: Option<i64>| bound.map(|b| b as i128 * mul + off);
+    let (lo, hi) = match mul >= 0 {
+        true => (at(left.lo), at(left.hi)),
+        false => (at(left.hi), at(left.lo)),
+    };
+
+    // range of `right - ( left * mul + off )`, each relation holds around one difference
+    let min = right.lo.zip(hi).map(|(r, h)| r as i128 - h);
+    let max = right.hi.zip(lo).map(|(r, l)| r as i128 - l);
+    let ideal = match linear.cmp() {
+        Compare::LT => 1,
+        Compare::GT => -1,
+        Compare::LTE | Compare::GTE | Compare::EQ => 0,
+    };
+    let diff = min.map_or(ideal, |m| ideal.max(m));
+    let diff = max.map_or(diff, |m| diff.min(m));
+
+    let distance = match linear.cmp() {
+        Compare::LTE => -diff,
+        Compare::LT => 1 - diff,
+        Compare::GTE => diff,
+        Compare::GT => diff + 1,
+        Compare::EQ => diff.abs(),
+    };
+    distance.clamp(0, i64::MAX as i128) as i64
+}
+
+/// weighted distances of the violated soft constraints, higher priorities first
+pub(super) struct Penalties {
+    soft: Vec<(Constraint, Soft)>,
+    /// priorities in the order of the cost
+    levels: Vec<u32>,
+    /// value satisfying the first soft constraint of a variable
+    preferred: HashMap<usize, i64>,
+}
+
+impl Penalties {
+    pub(super) fn new(model: &Model) -> Self {
+        let soft: Vec<_> = model
+            .constraints()
+            .into_iter()
+            .filter_map(|(cid, c)| Some((c, model.soft_of(cid)?)))
+            .collect();
+
+        let mut levels: Vec<_> = soft.iter().map(|(_, s)| s.priority).collect();
+        levels.sort_by(|a, b| b.cmp(a));
+        levels.dedup();
+
+        let mut preferred = HashMap::new();
+        for (c, _) in soft.iter() {
+            if let Constraint::Single(c) = c {
+                let value = clamp(0, &satisfying(c));
+                preferred.entry(c.id()).or_insert(value);
+            }
+        }
+
+        Self {
+            soft,
+            levels,
+            preferred,
+        }
+    }
+}
+
+impl Objective for Penalties {
+    fn bound(&self, domains: &HashMap<usize, Domain>) -> Vec<i64> {
+        let domain = |id: usize| domains.get(&id).copied().unwrap_or_default();
+        let mut cost = vec![0i64; self.levels.len()];
+
+        for (constraint, soft) in self.soft.iter() {
+            let distance = match constraint {
+                Constraint::Single(c) => {
+                    // the closest value of the domain to the satisfying ones
+                    let value = clamp(clamp(0, &satisfying(c)), &domain(c.id()));
+                    constraint
+                        .distance(&[value])
+                        .expect("one value for a single constraint")
+                }
+                Constraint::Double(DoubleConstrain::LinearRelation(l)) => {
+                    least_linear(l, &domain(l.left()), &domain(l.right()))
+                }
+            };
+            let level = self
+                .levels
+                .iter()
+                .position(|p| *p == soft.priority)
+                .expect("levels hold every priority");
+            cost[level] = cost[level].saturating_add(soft.penalty(distance));
+        }
+
+        cost
+    }
+
+    fn target(&self, var: usize, _: &Domain) -> i64 {
+        self.preferred.get(&var).copied().unwrap_or(0)
+    }
+}
+
+impl Model {
+    /// assignment of every variable satisfying the hard constraints, with the least penalties of
+    /// violated soft constraints. penalties of a priority are minimized before the lower ones,
+    /// `cost` of the solution holds them by descending priority.
+    pub fn solve(&self) -> Result<Solution, NoSolution> {
+        search(self, HashMap::new(), &Penalties::new(self), NODE_LIMIT)
+    }
+}
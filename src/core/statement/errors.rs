@@ -0,0 +1,68 @@
+use std::fmt::Display;
+
+use super::super::constraints::BoundaryError;
+
+/// statement or expression that does not parse, or that no constraint of a model can hold
+#[derive(Debug)]
+pub enum StatementError {
+    UnknownVariable(String),
+    /// unexpected character or token, `None` at the end of the source
+    Unexpected(Option<String>),
+    Overflow,
+    /// a statement without `<`, `<=`, `>`, `>=` or `=`
+    NotAComparison,
+    /// number of variables left after simplifying, constraints relate one or two
+    Variables(usize),
+    /// the variable with the greater id is not multiplied by 1 or -1
+    Coefficient(String),
+    /// multiple of a variable equal to a value that is not a multiple, as `2 * x = 3`
+    NoInteger,
+    /// chained comparisons that do not bound a single variable from both sides
+    Chain,
+    Boundary(BoundaryError<i64>),
+}
+
+impl From<BoundaryError<i64>> for StatementError {
+    fn from(value: BoundaryError<i64>) -> Self {
+        Self::Boundary(value)
+    }
+}
+
+impl Display for StatementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatementError::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            StatementError::Unexpected(Some(token)) => write!(f, "unexpected `{}`", token),
+            StatementError::Unexpected(None) => write!(f, "unexpected end of the expression"),
+            StatementError::Overflow => write!(f, "number out of range"),
+            StatementError::NotAComparison => {
+                write!(f, "expected a comparison as `x < 5` or `2 * x <= y`")
+            }
+            StatementError::Variables(n) => write!(
+                f,
+                "a constraint relates one or two variables, this one has {}",
+                n
+            ),
+            StatementError::Coefficient(name) => write!(
+                f,
+                "`{}` must be multiplied by 1 or -1, it has the greater id of both variables",
+                name
+            ),
+            StatementError::NoInteger => write!(f, "no integer value satisfies it"),
+            StatementError::Chain => write!(
+                f,
+                "chained comparisons must bound one variable from both sides, as `0 <= x < 20`"
+            ),
+            StatementError::Boundary(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StatementError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StatementError::Boundary(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,497 @@
+//! constraints written as formulas, the way [`Model::describe`] prints them
+//!
+//! ```text
+//! 0 <= x < 20
+//! x = 3
+//! x * 2 + 1 <= y
+//! 2 * x - 1 < y
+//! ```
+//!
+//! both sides of a comparison are sums of terms ( `3`, `x`, `2 * x` or `x * 2` ), variables
+//! are referred to by name or by id ( `#3` ). a statement holds one or two variables, the one
+//! with the greater id must be multiplied by 1 or -1 once simplified. multiples of a single
+//! variable are rounded to integer limits, `3 * x <= 10` is `x <= 3`.
+
+mod errors;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::iter::Peekable;
+
+use super::model::{
+    Boundary, Compare, Constraint, ConstraintId, DoubleConstrain, Fixed, Limit, Linear, Model,
+    SingleConstrain,
+};
+
+pub use self::errors::StatementError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Plus,
+    Minus,
+    Times,
+    Cmp(Compare),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Times => write!(f, "*"),
+            Token::Cmp(cmp) => write!(f, "{}", cmp.symbol()),
+        }
+    }
+}
+
+type Tokens = Peekable<std::vec::IntoIter<Token>>;
+
+fn tokenize(src: &str) -> Result<Vec<Token>, StatementError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '+' => tokens.push(Token::Plus),
+            '-' => tokens.push(Token::Minus),
+            '*' => tokens.push(Token::Times),
+            '<' => match chars.next_if_eq(&'=') {
+                Some(_) => tokens.push(Token::Cmp(Compare::LTE)),
+                None => tokens.push(Token::Cmp(Compare::LT)),
+            },
+            '>' => match chars.next_if_eq(&'=') {
+                Some(_) => tokens.push(Token::Cmp(Compare::GTE)),
+                None => tokens.push(Token::Cmp(Compare::GT)),
+            },
+            '=' => {
+                chars.next_if_eq(&'=');
+                tokens.push(Token::Cmp(Compare::EQ));
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = c.to_string();
+                while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                }
+                tokens.push(Token::Number(
+                    digits.parse().map_err(|_| StatementError::Overflow)?,
+                ));
+            }
+            c if c.is_alphabetic() || c == '_' || c == '#' => {
+                let mut name = c.to_string();
+                while let Some(d) = chars.next_if(|d| d.is_alphanumeric() || *d == '_') {
+                    name.push(d);
+                }
+                tokens.push(Token::Name(name));
+            }
+            c => return Err(StatementError::Unexpected(Some(c.to_string()))),
+        }
+    }
+    Ok(tokens)
+}
+
+fn unexpected(token: Option<Token>) -> StatementError {
+    StatementError::Unexpected(token.map(|t| t.to_string()))
+}
+
+/// sum of terms as written, variables by name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sum {
+    /// ( variable, coefficient ) in the order of the source, a variable may repeat
+    pub terms: Vec<(String, i64)>,
+    pub constant: i64,
+}
+
+impl Sum {
+    /// parse sums of terms as `3`, `x`, `2 * x` or `x * 2`
+    pub fn parse(src: &str) -> Result<Self, StatementError> {
+        let mut tokens = tokenize(src)?.into_iter().peekable();
+        let sum = Self::read(&mut tokens)?;
+        match tokens.next() {
+            None => Ok(sum),
+            t => Err(unexpected(t)),
+        }
+    }
+
+    /// terms up to a comparison or the end
+    fn read(tokens: &mut Tokens) -> Result<Self, StatementError> {
+        let mut sum = Self::default();
+
+        // a leading sign is optional
+        let mut sign = match tokens.next_if(|t| matches!(t, Token::Plus | Token::Minus)) {
+            Some(Token::Minus) => -1,
+            _ => 1,
+        };
+        loop {
+            let (coefficient, name) = match tokens.next() {
+                Some(Token::Number(n)) => match tokens.next_if_eq(&Token::Times) {
+                    Some(_) => match tokens.next() {
+                        Some(Token::Name(name)) => (n, Some(name)),
+                        t => return Err(unexpected(t)),
+                    },
+                    None => (n, None),
+                },
+                Some(Token::Name(name)) => match tokens.next_if_eq(&Token::Times) {
+                    Some(_) => match tokens.next() {
+                        Some(Token::Number(n)) => (n, Some(name)),
+                        t => return Err(unexpected(t)),
+                    },
+                    None => (1, Some(name)),
+                },
+                t => return Err(unexpected(t)),
+            };
+
+            let term = coefficient
+                .checked_mul(sign)
+                .ok_or(StatementError::Overflow)?;
+            match name {
+                Some(name) => sum.terms.push((name, term)),
+                None => {
+                    sum.constant = sum
+                        .constant
+                        .checked_add(term)
+                        .ok_or(StatementError::Overflow)?
+                }
+            }
+
+            sign = match tokens.peek() {
+                None | Some(Token::Cmp(_)) => break,
+                Some(Token::Plus) => 1,
+                Some(Token::Minus) => -1,
+                Some(_) => return Err(unexpected(tokens.next())),
+            };
+            tokens.next();
+        }
+        Ok(sum)
+    }
+}
+
+/// a constraint written as a formula
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub constraint: Constraint,
+    /// names the model does not know yet, with the ids given to them
+    pub names: Vec<(usize, String)>,
+}
+
+/// `a` for `b`, the comparison seen from the other side
+fn flip(cmp: Compare) -> Compare {
+    match cmp {
+        Compare::LT => Compare::GT,
+        Compare::LTE => Compare::GTE,
+        Compare::GT => Compare::LT,
+        Compare::GTE => Compare::LTE,
+        Compare::EQ => Compare::EQ,
+    }
+}
+
+fn narrow(value: i128) -> Result<i64, StatementError> {
+    i64::try_from(value).map_err(|_| StatementError::Overflow)
+}
+
+/// ids of the names of a statement, unknown names get ids after every one in use
+struct Variables<'a> {
+    model: &'a Model,
+    ids: HashMap<String, usize>,
+    names: Vec<(usize, String)>,
+}
+
+impl<'a> Variables<'a> {
+    fn new(model: &'a Model, sums: &[Sum]) -> Result<Self, StatementError> {
+        let mut ids = HashMap::new();
+        let mut unknown = Vec::new();
+        for (name, _) in sums.iter().flat_map(|s| s.terms.iter()) {
+            match model.lookup(name) {
+                Some(id) => {
+                    ids.insert(name.clone(), id);
+                }
+                None if name.starts_with('#') => {
+                    return Err(StatementError::UnknownVariable(name.clone()))
+                }
+                None if !unknown.contains(name) => unknown.push(name.clone()),
+                None => {}
+            }
+        }
+
+        let next = model
+            .variables()
+            .into_iter()
+            .chain(ids.values().copied())
+            .max()
+            .map_or(0, |id| id + 1);
+        let names: Vec<_> = (next..).zip(unknown).collect();
+        for (id, name) in names.iter() {
+            ids.insert(name.clone(), *id);
+        }
+        Ok(Self { model, ids, names })
+    }
+
+    fn name(&self, id: usize) -> String {
+        match self.names.iter().find(|(i, _)| *i == id) {
+            Some((_, name)) => name.clone(),
+            None => match self.model.name(id) {
+                Some(name) => name.to_string(),
+                None => format!("#{}", id),
+            },
+        }
+    }
+
+    /// `lhs - rhs` as terms ordered by id without zero coefficients, and a constant
+    fn difference(&self, lhs: &Sum, rhs: &Sum) -> (Vec<(usize, i128)>, i128) {
+        let mut coefficients: HashMap<usize, i128> = HashMap::new();
+        for (sign, sum) in [(1, lhs), (-1, rhs)] {
+            for (name, c) in sum.terms.iter() {
+                *coefficients.entry(self.ids[name]).or_default() += sign * *c as i128;
+            }
+        }
+        let mut terms: Vec<_> = coefficients.into_iter().filter(|(_, c)| *c != 0).collect();
+        terms.sort();
+        (terms, lhs.constant as i128 - rhs.constant as i128)
+    }
+
+    /// constraint holding when `terms + constant cmp 0`
+    fn relation(
+        &self,
+        terms: &[(usize, i128)],
+        constant: i128,
+        cmp: Compare,
+    ) -> Result<Constraint, StatementError> {
+        match *terms {
+            [(id, c)] => single(id, c, constant, cmp),
+            [(left, a), (right, b)] => {
+                // `a * left + constant + b * right cmp 0` with `b` of 1 or -1
+                let (mul, off, cmp) = match b {
+                    -1 => (a, constant, cmp),
+                    1 => (-a, -constant, flip(cmp)),
+                    _ => return Err(StatementError::Coefficient(self.name(right))),
+                };
+                let (mul, off) = (narrow(mul)?, narrow(off)?);
+                Ok(Constraint::Double(DoubleConstrain::LinearRelation(
+                    Linear::new(
+                        left,
+                        right,
+                        (mul != 1).then_some(mul),
+                        (off != 0).then_some(off),
+                        cmp,
+                    ),
+                )))
+            }
+            _ => Err(StatementError::Variables(terms.len())),
+        }
+    }
+}
+
+/// constraint holding when `c * id + constant cmp 0`
+fn single(id: usize, c: i128, constant: i128, cmp: Compare) -> Result<Constraint, StatementError> {
+    // `c * id cmp value` with a positive `c`
+    let (c, value, cmp) = match c > 0 {
+        true => (c, -constant, cmp),
+        false => (-c, constant, flip(cmp)),
+    };
+    let floor = value.div_euclid(c);
+    let ceil = -(-value).div_euclid(c);
+
+    let limit = |point: i128, equal: bool| -> Result<Limit<i64>, StatementError> {
+        Ok(Limit {
+            point: narrow(point)?,
+            equal,
+        })
+    };
+    let mut boundary = Boundary::new(id);
+    match cmp {
+        Compare::EQ => {
+            if value % c != 0 {
+                return Err(StatementError::NoInteger);
+            }
+            return Ok(Constraint::Single(SingleConstrain::Fixed(Fixed::new(
+                id,
+                narrow(value / c)?,
+            ))));
+        }
+        Compare::LT => boundary.top = Some(limit(ceil, false)?.into()),
+        Compare::LTE => boundary.top = Some(limit(floor, true)?.into()),
+        Compare::GT => boundary.bot = Some(limit(floor, false)?.into()),
+        Compare::GTE => boundary.bot = Some(limit(ceil, true)?.into()),
+    }
+    Ok(Constraint::Single(SingleConstrain::Boundary(boundary)))
+}
+
+/// boundary of `0 <= x` and `x < 20` written as `0 <= x < 20`
+fn chain(first: Constraint, second: Constraint) -> Result<Constraint, StatementError> {
+    let (
+        Constraint::Single(SingleConstrain::Boundary(first)),
+        Constraint::Single(SingleConstrain::Boundary(second)),
+    ) = (first, second)
+    else {
+        return Err(StatementError::Chain);
+    };
+    if first.id() != second.id() {
+        return Err(StatementError::Chain);
+    }
+    let (top, bot) = match (first.top, first.bot, second.top, second.bot) {
+        (Some(top), None, None, Some(bot)) | (None, Some(bot), Some(top), None) => (top, bot),
+        _ => return Err(StatementError::Chain),
+    };
+    let boundary = Boundary::create(first.id(), Some(top.limit), Some(bot.limit))?;
+    Ok(Constraint::Single(SingleConstrain::Boundary(boundary)))
+}
+
+impl Statement {
+    /// parse a comparison, or a chain of two bounding a single variable
+    pub fn parse(model: &Model, src: &str) -> Result<Self, StatementError> {
+        let mut tokens = tokenize(src)?.into_iter().peekable();
+        let mut sums = vec![Sum::read(&mut tokens)?];
+        let mut cmps = Vec::new();
+        // a sum ends at a comparison or at the end
+        while let Some(Token::Cmp(cmp)) = tokens.next() {
+            cmps.push(cmp);
+            sums.push(Sum::read(&mut tokens)?);
+        }
+
+        let vars = Variables::new(model, &sums)?;
+        let mut parts = Vec::new();
+        for (i, cmp) in cmps.into_iter().enumerate() {
+            let (terms, constant) = vars.difference(&sums[i], &sums[i + 1]);
+            parts.push(vars.relation(&terms, constant, cmp)?);
+        }
+        let constraint = match parts[..] {
+            [] => return Err(StatementError::NotAComparison),
+            [constraint] => constraint,
+            [first, second] => chain(first, second)?,
+            _ => return Err(StatementError::Chain),
+        };
+
+        Ok(Self {
+            constraint,
+            names: vars.names,
+        })
+    }
+}
+
+impl Model {
+    /// add the constraint of a statement, unknown names become new variables
+    pub fn add_statement(&mut self, src: &str) -> Result<ConstraintId, StatementError> {
+        let statement = Statement::parse(self, src)?;
+        for (id, name) in statement.names {
+            self.set_name(id, name);
+        }
+        Ok(self
+            .add(statement.constraint)
+            .expect("statements order the variables of relations"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// x, y and z with the ids 0, 1 and 2
+    fn model() -> Model {
+        let mut model = Model::new();
+        for (id, name) in ["x", "y", "z"].into_iter().enumerate() {
+            model.set_name(id, name);
+        }
+        model
+    }
+
+    fn parsed(src: &str) -> Result<String, String> {
+        let model = model();
+        Statement::parse(&model, src)
+            .map(|s| model.describe(&s.constraint))
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn sums_keep_the_terms_as_written() {
+        let sum = Sum::parse("2 * x - x * 3 + 4 - 1").unwrap();
+        assert_eq!(sum.terms, vec![("x".to_string(), 2), ("x".to_string(), -3)]);
+        assert_eq!(sum.constant, 3);
+        assert_eq!(Sum::parse("-x").unwrap().terms, vec![("x".to_string(), -1)]);
+        assert!(Sum::parse("x +").is_err());
+        assert!(Sum::parse("x < 3").is_err());
+    }
+
+    #[test]
+    fn multiples_are_rounded_to_integer_limits() {
+        let cases = [
+            ("3 * x <= 10", "x <= 3"),
+            ("3 * x < 10", "x < 4"),
+            ("3 * x > 10", "3 < x"),
+            ("3 * x >= 10", "4 <= x"),
+            ("-2 * x <= 5", "-2 <= x"),
+            ("-2 * x < 5", "-3 < x"),
+            ("-2 * x > 5", "x < -2"),
+            ("-2 * x >= 5", "x <= -3"),
+            ("-2 * x = 6", "x = -3"),
+            ("0 <= x < 20", "0 <= x < 20"),
+            ("20 > x >= 0", "0 <= x < 20"),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(parsed(src).as_deref(), Ok(expected), "{}", src);
+        }
+        assert_eq!(
+            parsed("2 * x = 3"),
+            Err("no integer value satisfies it".to_string())
+        );
+    }
+
+    #[test]
+    fn terms_move_to_one_side() {
+        assert_eq!(parsed("x + 3 <= y - 2").as_deref(), Ok("x + 5 <= y"));
+        assert_eq!(parsed("y >= x + 5").as_deref(), Ok("x + 5 <= y"));
+        assert_eq!(parsed("x + y - y < 4").as_deref(), Ok("x < 4"));
+        assert_eq!(parsed("2 * x - 1 < y").as_deref(), Ok("x * 2 - 1 < y"));
+        // the variable with the greater id is written on the left
+        assert_eq!(parsed("y <= 2 * x").as_deref(), Ok("x * 2 >= y"));
+        assert!(parsed("2 * y <= x").is_err_and(|e| e.contains("`y` must be multiplied")));
+    }
+
+    #[test]
+    fn unknown_names_become_new_variables() {
+        let model = model();
+        let statement = Statement::parse(&model, "w < x + 1").unwrap();
+        assert_eq!(statement.names, vec![(3, "w".to_string())]);
+        assert!(statement.constraint.involves(0) && statement.constraint.involves(3));
+
+        // ids need no declaration, names after a `#` are no ids
+        assert_eq!(parsed("#7 < 3").as_deref(), Ok("#7 < 3"));
+        assert_eq!(parsed("#x < 3"), Err("unknown variable `#x`".to_string()));
+        assert_eq!(parsed("#2 < 3").as_deref(), Ok("z < 3"));
+    }
+
+    #[test]
+    fn malformed_statements_are_rejected() {
+        let errors = [
+            ("x + 1", "expected a comparison"),
+            ("x + y + z < 1", "this one has 3"),
+            ("0 <= x < y", "chained comparisons"),
+            ("x < 3 < y", "chained comparisons"),
+            ("x < 3 $", "unexpected `$`"),
+            ("x <", "unexpected end"),
+        ];
+        for (src, expected) in errors {
+            let err = parsed(src).unwrap_err();
+            assert!(err.contains(expected), "{}: {}", src, err);
+        }
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        for src in [
+            "x < 9223372036854775808",
+            "x + 9223372036854775807 + 1 < 0",
+            "x - 9223372036854775807 <= y + 9223372036854775807",
+            "-9223372036854775807 * x - x - x < y",
+        ] {
+            assert_eq!(
+                parsed(src),
+                Err("number out of range".to_string()),
+                "{}",
+                src
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum TableError {
+    /// unreadable file, broken utf-8 or quoting
+    Csv(csv::Error),
+    /// two columns hold the same variable
+    DuplicateColumn { column: String, var: usize },
+}
+
+impl From<csv::Error> for TableError {
+    fn from(value: csv::Error) -> Self {
+        Self::Csv(value)
+    }
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Csv(e) => write!(f, "{}", e),
+            TableError::DuplicateColumn { column, var } => {
+                write!(f, "column `{}` repeats variable #{}", column, var)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TableError::Csv(e) => Some(e),
+            TableError::DuplicateColumn { .. } => None,
+        }
+    }
+}
+
+/// value of a cell that is not an integer, the row is skipped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellError {
+    /// line of the row in the file, 1-indexed
+    pub line: u64,
+    pub column: String,
+    pub value: String,
+}
+
+impl Display for CellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: `{}` in column `{}` is not an integer",
+            self.line, self.value, self.column
+        )
+    }
+}
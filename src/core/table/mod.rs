@@ -0,0 +1,311 @@
+//! checking the rows of a csv file against a model
+//!
+//! ```csv
+//! x,y,comment
+//! 5,12,first
+//! 25,40,
+//! ```
+//!
+//! the header names the variable of every column, by declared name or by id ( `3` or `#3` ).
+//! other columns are ignored, an empty cell leaves the variable without a value.
+
+mod errors;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::io;
+use std::path::Path;
+
+use super::model::{Assignment, CheckReport, ConstraintId, Model, Plan};
+
+pub use self::errors::{CellError, TableError};
+
+/// rows checked at once
+const BATCH: usize = 4096;
+
+/// report of a row with a violation or a missing value
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowReport {
+    /// line of the row in the file, 1-indexed
+    pub line: u64,
+    pub report: CheckReport,
+}
+
+/// result of [`validate`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Validation {
+    /// rows read, unreadable ones included
+    pub rows: usize,
+    /// rows with every constraint evaluated and holding
+    pub valid: usize,
+    /// every other readable row, in file order
+    pub failures: Vec<RowReport>,
+    /// cells which are not integers, their rows are not checked
+    pub invalid: Vec<CellError>,
+    /// number of rows violating a constraint, soft ones included
+    pub by_constraint: BTreeMap<ConstraintId, usize>,
+    /// formulas of the constraints in `by_constraint`
+    pub formulas: HashMap<ConstraintId, String>,
+    /// headers not naming a variable
+    pub ignored: Vec<String>,
+}
+
+impl Validation {
+    /// every row was read and no hard constraint is violated
+    pub fn is_ok(&self) -> bool {
+        self.invalid.is_empty() && self.failures.iter().all(|r| r.report.is_ok())
+    }
+
+    /// counts of rows and violations by constraint, without the reports of the rows
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} rows, {} valid, {} failed, {} unreadable",
+            self.rows,
+            self.valid,
+            self.failures.len(),
+            self.rows - self.valid - self.failures.len()
+        );
+        for (cid, count) in self.by_constraint.iter() {
+            out.push_str(&format!(
+                "\n  {}: {} violated in {} rows",
+                cid, self.formulas[cid], count
+            ));
+        }
+        if !self.ignored.is_empty() {
+            out.push_str(&format!("\nignored columns: {}", self.ignored.join(", ")));
+        }
+        out
+    }
+}
+
+impl Display for Validation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for e in self.invalid.iter() {
+            writeln!(f, "{}", e)?;
+        }
+        for row in self.failures.iter() {
+            let report = &row.report;
+            if !report.missing.is_empty() {
+                let missing: Vec<_> = report
+                    .missing
+                    .iter()
+                    .map(|id| match report.names.get(id) {
+                        Some(name) => name.clone(),
+                        None => format!("#{}", id),
+                    })
+                    .collect();
+                writeln!(
+                    f,
+                    "line {}: missing values: {}",
+                    row.line,
+                    missing.join(", ")
+                )?;
+            }
+            for v in report.violations.iter().chain(report.soft.iter()) {
+                writeln!(f, "line {}: {}", row.line, report.line(v))?;
+            }
+        }
+
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// collects the reports of the rows
+struct Collector<'a> {
+    model: &'a Model,
+    plan: Plan,
+    validation: Validation,
+    /// ( line, row ) not checked yet
+    batch: Vec<(u64, Assignment)>,
+}
+
+impl Collector<'_> {
+    fn flush(&mut self) {
+        let (lines, rows): (Vec<_>, Vec<_>) = self.batch.drain(..).unzip();
+        let reports = self.plan.check_all(&rows);
+
+        for (line, report) in lines.into_iter().zip(reports) {
+            if report.is_ok() && report.soft.is_empty() {
+                self.validation.valid += 1;
+                continue;
+            }
+            for v in report.violations.iter().chain(report.soft.iter()) {
+                *self.validation.by_constraint.entry(v.cid).or_default() += 1;
+                self.validation
+                    .formulas
+                    .entry(v.cid)
+                    .or_insert_with(|| self.model.describe(&v.constraint));
+            }
+            self.validation.failures.push(RowReport { line, report });
+        }
+    }
+}
+
+/// check every row of the csv `reader` against `model`
+pub fn validate<R: io::Read>(model: &Model, reader: R) -> Result<Validation, TableError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = reader.headers()?.clone();
+    let mut columns = Vec::new();
+    let mut ignored = Vec::new();
+    for header in headers.iter() {
+        let var = model.lookup(header);
+        match var {
+            Some(var) if columns.contains(&Some(var)) => {
+                return Err(TableError::DuplicateColumn {
+                    column: header.to_string(),
+                    var,
+                })
+            }
+            Some(_) => {}
+            None => ignored.push(header.to_string()),
+        }
+        columns.push(var);
+    }
+
+    let mut collector = Collector {
+        model,
+        plan: model.compile(),
+        validation: Validation {
+            ignored,
+            ..Default::default()
+        },
+        batch: Vec::with_capacity(BATCH),
+    };
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        collector.validation.rows += 1;
+        let line = record.position().map_or(0, |p| p.line());
+
+        let mut row = Assignment::new();
+        let mut readable = true;
+        for (cell, (var, header)) in record.iter().zip(columns.iter().zip(headers.iter())) {
+            let Some(var) = var else {
+                continue;
+            };
+            if cell.is_empty() {
+                continue;
+            }
+            match cell.parse() {
+                Ok(value) => {
+                    row.insert(*var, value);
+                }
+                Err(_) => {
+                    readable = false;
+                    collector.validation.invalid.push(CellError {
+                        line,
+                        column: header.to_string(),
+                        value: cell.to_string(),
+                    });
+                }
+            }
+        }
+
+        if readable {
+            collector.batch.push((line, row));
+        }
+        if collector.batch.len() >= BATCH {
+            collector.flush();
+        }
+    }
+    collector.flush();
+
+    Ok(collector.validation)
+}
+
+/// check every row of the csv file at `path` against `model`
+pub fn validate_path(model: &Model, path: &Path) -> Result<Validation, TableError> {
+    let file = std::fs::File::open(path).map_err(csv::Error::from)?;
+    validate(model, io::BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::model::{Limit, Model};
+    use super::*;
+
+    /// `x <= 10` and `#3 = 1`
+    fn model() -> Model {
+        let mut model = Model::new();
+        model.set_name(0, "x");
+        let top = Limit {
+            point: 10,
+            equal: true,
+        };
+        model.add_boundary(0, Some(top), None).unwrap();
+        model.add_fixed(3, 1);
+        model
+    }
+
+    #[test]
+    fn rows_are_reported_by_line() {
+        let csv = "x,#3,comment\n5,1,ok\n11,1,\n,1,\na,1,\n20,2,\n";
+        let validation = validate(&model(), csv.as_bytes()).unwrap();
+
+        assert_eq!(validation.rows, 5);
+        assert_eq!(validation.valid, 1);
+        let lines: Vec<_> = validation.failures.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![3, 4, 6]);
+        // the empty cell leaves x without a value
+        assert_eq!(validation.failures[1].report.missing, [0].into());
+        assert_eq!(
+            validation.invalid,
+            vec![CellError {
+                line: 5,
+                column: "x".to_string(),
+                value: "a".to_string(),
+            }]
+        );
+        assert_eq!(
+            validation.by_constraint,
+            BTreeMap::from([(ConstraintId(0), 2), (ConstraintId(1), 1)])
+        );
+        assert_eq!(validation.ignored, vec!["comment".to_string()]);
+        assert!(!validation.is_ok());
+        assert_eq!(
+            validation.summary(),
+            "5 rows, 1 valid, 3 failed, 1 unreadable
+  c0: x <= 10 violated in 2 rows
+  c1: #3 = 1 violated in 1 rows
+ignored columns: comment"
+        );
+        assert!(validation
+            .to_string()
+            .starts_with("line 5: `a` in column `x` is not an integer\nline 3: c0: x <= 10"));
+    }
+
+    #[test]
+    fn headers_name_variables_by_name_or_id() {
+        let validation = validate(&model(), "3,0\n1,10\n".as_bytes()).unwrap();
+        assert!(validation.is_ok());
+        assert_eq!(validation.valid, 1);
+        assert!(validation.ignored.is_empty());
+
+        let err = validate(&model(), "x,#0\n1,1\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "column `#0` repeats variable #0");
+    }
+
+    #[test]
+    fn rows_beyond_one_batch_keep_their_lines() {
+        let mut csv = "x,#3\n".to_string();
+        for i in 0..BATCH * 2 + 10 {
+            let x = if i % 1000 == 999 { 11 } else { 0 };
+            csv.push_str(&format!("{},1\n", x));
+        }
+        let validation = validate(&model(), csv.as_bytes()).unwrap();
+
+        assert_eq!(validation.rows, BATCH * 2 + 10);
+        let lines: Vec<_> = validation.failures.iter().map(|r| r.line).collect();
+        // the header is line 1, row i is on line i + 2
+        let expected: Vec<_> = (0..BATCH as u64 * 2 + 10)
+            .filter(|i| i % 1000 == 999)
+            .map(|i| i + 2)
+            .collect();
+        assert_eq!(lines, expected);
+        assert_eq!(validation.valid, validation.rows - expected.len());
+        assert_eq!(validation.by_constraint[&ConstraintId(0)], expected.len());
+    }
+}
@@ -1,2 +1,4 @@
-mod core;
+pub mod cli;
+pub mod core;
+pub mod lsp;
 pub mod ui;
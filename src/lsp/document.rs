@@ -0,0 +1,501 @@
+use std::collections::HashMap;
+
+use crate::core::file::node::{self, Node, Value};
+use crate::core::file::{FileError, Format, ModelFile, Position, VarRef};
+use crate::core::model::{ConstraintId, Model};
+
+/// text of one line between two positions, the end is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    /// a cursor right after the text is still on it
+    fn contains(&self, pos: Position) -> bool {
+        self.start <= pos && pos <= self.end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+}
+
+/// variable declared by a `variable` entry
+#[derive(Debug, Clone)]
+struct Declaration {
+    id: usize,
+    name: Option<String>,
+    /// span of the name, of the id for unnamed variables
+    span: Span,
+}
+
+/// proposed variable name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub label: String,
+    pub insert: String,
+    pub detail: String,
+}
+
+/// a model file as an editor holds it, with the positions of its variables
+#[derive(Debug)]
+pub struct Document {
+    pub src: String,
+    pub format: Format,
+    declarations: Vec<Declaration>,
+    /// variables referred to by constraints
+    references: Vec<(Span, VarRef)>,
+    /// first key of every constraint entry with its `cid`, in the order of the file
+    entries: Vec<(Span, Option<usize>)>,
+    /// `None` if the file has errors
+    pub model: Option<Model>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Document {
+    pub fn new(src: String, format: Format) -> Self {
+        let mut document = Self {
+            src,
+            format,
+            declarations: Vec::new(),
+            references: Vec::new(),
+            entries: Vec::new(),
+            model: None,
+            diagnostics: Vec::new(),
+        };
+        let root = match format {
+            Format::Toml => node::parse_toml(&document.src),
+            Format::Yaml => node::parse_yaml(&document.src),
+        };
+        match root {
+            Ok(root) => {
+                document.index(&root);
+                document.check(&root);
+            }
+            Err(e) => document.error(e),
+        }
+        document
+    }
+
+    /// text from `pos` to the end of the value or key starting there
+    fn span(&self, pos: Position) -> Span {
+        let line = self.src.lines().nth(pos.line - 1).unwrap_or_default();
+        let mut chars = line.chars().skip(pos.column - 1);
+        let len = match chars.next() {
+            Some(quote @ ('"' | '\'')) => match chars.position(|c| c == quote) {
+                Some(i) => i + 2,
+                None => line.chars().count() + 1 - pos.column,
+            },
+            Some(_) => {
+                1 + chars
+                    .take_while(|c| !c.is_whitespace() && !",:=#]}".contains(*c))
+                    .count()
+            }
+            None => 0,
+        };
+        Span {
+            start: pos,
+            end: Position {
+                line: pos.line,
+                column: pos.column + len,
+            },
+        }
+    }
+
+    fn error(&mut self, e: FileError) {
+        self.diagnostics.push(Diagnostic {
+            span: self.span(e.pos),
+            message: e.kind.to_string(),
+        });
+    }
+
+    /// find the declarations, references and constraint entries, as far as the tree is valid
+    fn index(&mut self, root: &Node) {
+        let Value::Table(sections) = &root.value else {
+            return;
+        };
+        let entries = |section: &'static str| {
+            sections
+                .iter()
+                .filter(move |(key, _)| key.name == section)
+                .flat_map(|(_, node)| match &node.value {
+                    Value::Array(entries) => entries.as_slice(),
+                    _ => &[],
+                })
+                .filter_map(|entry| match &entry.value {
+                    Value::Table(fields) => Some(fields),
+                    _ => None,
+                })
+        };
+
+        for fields in entries("variable") {
+            let get = |key: &str| fields.iter().find(|(k, _)| k.name == key).map(|(_, v)| v);
+            let Some(Node {
+                value: Value::Integer(id),
+                pos,
+            }) = get("id")
+            else {
+                continue;
+            };
+            let (name, pos) = match get("name") {
+                Some(Node {
+                    value: Value::String(name),
+                    pos,
+                }) => (Some(name.clone()), *pos),
+                _ => (None, *pos),
+            };
+            if let Ok(id) = usize::try_from(*id) {
+                let span = self.span(pos);
+                self.declarations.push(Declaration { id, name, span });
+            }
+        }
+
+        for section in ["boundary", "fixed", "linear"] {
+            for fields in entries(section) {
+                if let Some((key, _)) = fields.first() {
+                    let cid = fields.iter().find_map(|(k, v)| match v.value {
+                        Value::Integer(cid) if k.name == "cid" => usize::try_from(cid).ok(),
+                        _ => None,
+                    });
+                    self.entries.push((self.span(key.pos), cid));
+                }
+                for (key, value) in fields.iter() {
+                    if !["id", "left", "right"].contains(&key.name.as_str()) {
+                        continue;
+                    }
+                    let var = match &value.value {
+                        Value::String(name) => VarRef::Name(name.clone()),
+                        Value::Integer(id) => match usize::try_from(*id) {
+                            Ok(id) => VarRef::Id(id),
+                            Err(_) => continue,
+                        },
+                        _ => continue,
+                    };
+                    self.references.push((self.span(value.pos), var));
+                }
+            }
+        }
+    }
+
+    /// validate and build the model, then look for contradicting constraints
+    fn check(&mut self, root: &Node) {
+        let file = match ModelFile::from_node(root) {
+            Ok(file) => file,
+            Err(errors) => {
+                for e in errors {
+                    self.error(e);
+                }
+                return;
+            }
+        };
+        let model = match file.build() {
+            Ok(model) => model,
+            Err(kind) => {
+                self.error(FileError::new(Position::default(), kind));
+                return;
+            }
+        };
+
+        if let (Some(conflict), Err(e)) = (model.conflict(), model.propagated(&HashMap::new())) {
+            let var = self.name(&model, e.var);
+            let entries = self.entry_ids();
+            let spans: Vec<_> = conflict
+                .iter()
+                .filter_map(|cid| entries.get(cid).copied())
+                .collect();
+            for span in spans.iter() {
+                let others: Vec<_> = spans
+                    .iter()
+                    .filter(|s| *s != span)
+                    .map(|s| s.start.line.to_string())
+                    .collect();
+                let message = match others.len() {
+                    0 => format!("no value of {} satisfies this constraint", var),
+                    1 => format!(
+                        "contradicts the constraint at line {}, no value is left for {}",
+                        others[0], var
+                    ),
+                    _ => format!(
+                        "contradicts the constraints at lines {}, no value is left for {}",
+                        others.join(", "),
+                        var
+                    ),
+                };
+                self.diagnostics.push(Diagnostic {
+                    span: *span,
+                    message,
+                });
+            }
+        }
+        self.model = Some(model);
+    }
+
+    /// span of every constraint by its id, numbered as `ModelFile::build` does: entries keep
+    /// their `cid`, the others follow the greatest one in the order of the file
+    fn entry_ids(&self) -> HashMap<ConstraintId, Span> {
+        let mut next = self
+            .entries
+            .iter()
+            .filter_map(|(_, cid)| *cid)
+            .max()
+            .map_or(0, |cid| cid + 1);
+        self.entries
+            .iter()
+            .map(|(span, cid)| {
+                let cid = cid.unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                });
+                (ConstraintId(cid), *span)
+            })
+            .collect()
+    }
+
+    fn name(&self, model: &Model, id: usize) -> String {
+        match model.name(id) {
+            Some(name) => name.to_string(),
+            None => format!("#{}", id),
+        }
+    }
+
+    /// variable under the cursor, with the span of its mention
+    fn variable_at(&self, pos: Position) -> Option<(Span, usize)> {
+        let declared = self
+            .declarations
+            .iter()
+            .find(|d| d.span.contains(pos))
+            .map(|d| (d.span, d.id));
+        declared.or_else(|| {
+            let (span, var) = self.references.iter().find(|(s, _)| s.contains(pos))?;
+            let id = match var {
+                VarRef::Id(id) => *id,
+                VarRef::Name(name) => {
+                    self.declarations
+                        .iter()
+                        .find(|d| d.name.as_ref() == Some(name))?
+                        .id
+                }
+            };
+            Some((*span, id))
+        })
+    }
+
+    /// markdown with the propagated bounds and the constraints of the variable under the cursor
+    pub fn hover(&self, pos: Position) -> Option<(Span, String)> {
+        let (span, id) = self.variable_at(pos)?;
+        let name = self
+            .declarations
+            .iter()
+            .find(|d| d.id == id)
+            .and_then(|d| d.name.clone());
+        let mut text = match name {
+            Some(name) => format!("**{}** ( #{} )", name, id),
+            None => format!("**#{}**", id),
+        };
+
+        let Some(model) = &self.model else {
+            text.push_str("\n\nno bounds while the file has errors");
+            return Some((span, text));
+        };
+        match model.propagated(&HashMap::new()) {
+            Ok(domains) => {
+                let domain = domains.get(&id).copied().unwrap_or_default();
+                let lo = domain.lo.map_or("-inf".to_string(), |lo| lo.to_string());
+                let hi = domain.hi.map_or("inf".to_string(), |hi| hi.to_string());
+                text.push_str(&format!("\n\nbounds: [{}, {}]", lo, hi));
+            }
+            Err(e) => text.push_str(&format!(
+                "\n\ninfeasible, no value is left for {}",
+                self.name(model, e.var)
+            )),
+        }
+        for (cid, constraint) in model.constraints() {
+            if constraint.involves(id) {
+                text.push_str(&format!("\n\n`{}: {}`", cid, model.describe(&constraint)));
+            }
+        }
+        Some((span, text))
+    }
+
+    /// declaration of the variable under the cursor
+    pub fn definition(&self, pos: Position) -> Option<Span> {
+        let (_, id) = self.variable_at(pos)?;
+        self.declarations
+            .iter()
+            .find(|d| d.id == id)
+            .map(|d| d.span)
+    }
+
+    /// declared names, quoted unless the cursor is within a toml string already
+    pub fn completions(&self, pos: Position) -> Vec<Suggestion> {
+        let line = self.src.lines().nth(pos.line - 1).unwrap_or_default();
+        let before: String = line.chars().take(pos.column - 1).collect();
+        let word = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_');
+        let quoted = word.ends_with(['"', '\'']);
+
+        self.declarations
+            .iter()
+            .filter_map(|d| {
+                let name = d.name.clone()?;
+                let insert = match self.format {
+                    Format::Toml if !quoted => format!("\"{}\"", name),
+                    _ => name.clone(),
+                };
+                Some(Suggestion {
+                    label: name,
+                    insert,
+                    detail: format!("#{}", d.id),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEASIBLE: &str = r#"[[variable]]
+id = 0
+name = "x"
+
+[[variable]]
+id = 1
+
+[[boundary]]
+id = "x"
+top = { point = 20 }
+bot = { point = 0 }
+
+[[linear]]
+left = "x"
+right = 1
+cmp = "<="
+"#;
+
+    fn at(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    fn span(line: usize, start: usize, end: usize) -> Span {
+        Span {
+            start: at(line, start),
+            end: at(line, end),
+        }
+    }
+
+    #[test]
+    fn conflicts_point_at_the_entries_of_their_ids() {
+        // ids 9, 2 and 10 for the entries in the order of the file
+        let src = r#"[[variable]]
+id = 0
+name = "x"
+
+[[boundary]]
+cid = 9
+id = "x"
+top = { point = 20 }
+
+[[fixed]]
+cid = 2
+id = 1
+value = 4
+
+[[fixed]]
+id = "x"
+value = 30
+"#;
+        let document = Document::new(src.to_string(), Format::Toml);
+        assert!(document.model.is_some());
+        assert_eq!(
+            document.diagnostics,
+            vec![
+                Diagnostic {
+                    span: span(6, 1, 4),
+                    message: "contradicts the constraint at line 16, no value is left for x"
+                        .to_string(),
+                },
+                Diagnostic {
+                    span: span(16, 1, 3),
+                    message: "contradicts the constraint at line 6, no value is left for x"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn spans_cover_quoted_and_plain_values() {
+        let document = Document::new(FEASIBLE.to_string(), Format::Toml);
+        assert_eq!(document.span(at(14, 8)), span(14, 8, 11));
+        assert_eq!(document.span(at(15, 9)), span(15, 9, 10));
+        assert_eq!(document.span(at(10, 7)), span(10, 7, 8));
+    }
+
+    #[test]
+    fn hover_shows_bounds_and_constraints() {
+        let document = Document::new(FEASIBLE.to_string(), Format::Toml);
+        assert!(document.diagnostics.is_empty());
+
+        // by name, the end of the span still counts
+        let (span_of, text) = document.hover(at(14, 11)).unwrap();
+        assert_eq!(span_of, span(14, 8, 11));
+        assert_eq!(
+            text,
+            "**x** ( #0 )\n\nbounds: [0, 20]\n\n`c0: 0 <= x <= 20`\n\n`c1: x <= #1`"
+        );
+        // by id
+        let (span_of, text) = document.hover(at(15, 9)).unwrap();
+        assert_eq!(span_of, span(15, 9, 10));
+        assert_eq!(text, "**#1**\n\nbounds: [0, inf]\n\n`c1: x <= #1`");
+
+        assert_eq!(document.hover(at(16, 2)), None);
+    }
+
+    #[test]
+    fn definitions_are_the_declarations() {
+        let document = Document::new(FEASIBLE.to_string(), Format::Toml);
+        assert_eq!(document.definition(at(14, 9)), Some(span(3, 8, 11)));
+        assert_eq!(document.definition(at(9, 7)), Some(span(3, 8, 11)));
+        // an unnamed variable is declared by its id
+        assert_eq!(document.definition(at(15, 9)), Some(span(6, 6, 7)));
+        assert_eq!(document.definition(at(3, 9)), Some(span(3, 8, 11)));
+        assert_eq!(document.definition(at(16, 8)), None);
+    }
+
+    #[test]
+    fn completions_are_quoted_outside_of_strings() {
+        let document = Document::new(FEASIBLE.to_string(), Format::Toml);
+        let outside = document.completions(at(14, 8));
+        assert_eq!(
+            outside,
+            vec![Suggestion {
+                label: "x".to_string(),
+                insert: "\"x\"".to_string(),
+                detail: "#0".to_string(),
+            }]
+        );
+        let inside = document.completions(at(14, 10));
+        assert_eq!(inside[0].insert, "x");
+
+        let yaml = "variable:\n  - id: 0\n    name: x\nfixed:\n  - id: x\n    value: 1\n";
+        let document = Document::new(yaml.to_string(), Format::Yaml);
+        assert!(document.diagnostics.is_empty());
+        assert_eq!(document.completions(at(5, 11))[0].insert, "x");
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let src = "[[variable]]\nid = 0\nname = \"é😀\"\n\n[[fixed]]\nid = \"é😀\"\nvalue = 1\n";
+        let document = Document::new(src.to_string(), Format::Toml);
+        assert!(document.diagnostics.is_empty());
+        let (span_of, text) = document.hover(at(6, 7)).unwrap();
+        assert_eq!(span_of, span(6, 6, 10));
+        assert!(text.starts_with("**é😀** ( #0 )"));
+        assert_eq!(document.definition(at(6, 9)), Some(span(3, 8, 12)));
+    }
+}
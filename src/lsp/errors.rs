@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+use lsp_server::ProtocolError;
+
+#[derive(Debug)]
+pub enum LspError {
+    Protocol(ProtocolError),
+    Json(serde_json::Error),
+    Io(std::io::Error),
+    /// the client closed the connection while a message was sent
+    Disconnected,
+}
+
+impl From<ProtocolError> for LspError {
+    fn from(value: ProtocolError) -> Self {
+        Self::Protocol(value)
+    }
+}
+
+impl From<serde_json::Error> for LspError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl From<std::io::Error> for LspError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Display for LspError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LspError::Protocol(e) => write!(f, "{}", e),
+            LspError::Json(e) => write!(f, "{}", e),
+            LspError::Io(e) => write!(f, "{}", e),
+            LspError::Disconnected => write!(f, "the client disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for LspError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LspError::Protocol(e) => Some(e),
+            LspError::Json(e) => Some(e),
+            LspError::Io(e) => Some(e),
+            LspError::Disconnected => None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! language server for model files, run by `abstaction lsp` over stdio
+//!
+//! toml and yaml model files get diagnostics for syntax and validation errors, crossed limits
+//! and constraints contradicting each other ( see [`Model::conflict`] ). hovering a variable
+//! shows its propagated bounds and constraints, go to definition jumps to its `variable` entry
+//! and the names of declared variables are completed.
+//!
+//! [`Model::conflict`]: crate::core::model::Model::conflict
+
+mod document;
+mod errors;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationType, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as RequestType};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::core::file::{Format, Position};
+
+pub use self::document::{Diagnostic, Document, Span, Suggestion};
+pub use self::errors::LspError;
+
+/// position of the editor ( 0-indexed, utf-16 columns ) in `src`
+fn position(src: &str, pos: lsp_types::Position) -> Position {
+    let line = src.lines().nth(pos.line as usize).unwrap_or_default();
+    let mut units = 0;
+    let mut column = 1;
+    for c in line.chars() {
+        units += c.len_utf16();
+        if units > pos.character as usize {
+            break;
+        }
+        column += 1;
+    }
+    Position {
+        line: pos.line as usize + 1,
+        column,
+    }
+}
+
+/// position of the editor for `pos` in `src`
+fn lsp_position(src: &str, pos: Position) -> lsp_types::Position {
+    let line = src.lines().nth(pos.line - 1).unwrap_or_default();
+    let units: usize = line.chars().take(pos.column - 1).map(char::len_utf16).sum();
+    lsp_types::Position::new(pos.line as u32 - 1, units as u32)
+}
+
+fn range(src: &str, span: Span) -> lsp_types::Range {
+    lsp_types::Range::new(lsp_position(src, span.start), lsp_position(src, span.end))
+}
+
+/// answer a request with `handle` applied to its params
+fn respond<P, R>(id: RequestId, params: serde_json::Value, handle: impl FnOnce(P) -> R) -> Response
+where
+    P: DeserializeOwned,
+    R: Serialize,
+{
+    match serde_json::from_value(params) {
+        Ok(params) => Response::new_ok(id, handle(params)),
+        Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+    }
+}
+
+struct Server {
+    connection: Connection,
+    /// open model files
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn send(&self, message: impl Into<Message>) -> Result<(), LspError> {
+        self.connection
+            .sender
+            .send(message.into())
+            .map_err(|_| LspError::Disconnected)
+    }
+
+    fn serve(mut self) -> Result<(), LspError> {
+        let receiver = self.connection.receiver.clone();
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.request(request)?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Result<(), LspError> {
+        let Request { id, method, params } = request;
+        let response = match method.as_str() {
+            HoverRequest::METHOD => respond(id, params, |p| self.hover(p)),
+            GotoDefinition::METHOD => respond(id, params, |p| self.definition(p)),
+            Completion::METHOD => respond(id, params, |p| self.completion(p)),
+            _ => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request {}", method),
+            ),
+        };
+        self.send(response)
+    }
+
+    fn notification(&mut self, notification: Notification) -> Result<(), LspError> {
+        let Notification { method, params } = notification;
+        match method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(params)?;
+                self.update(params.text_document.uri, params.text_document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(params)?;
+                // the whole text is sent on every change
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(params.text_document.uri, change.text),
+                    None => Ok(()),
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+                if self.documents.remove(&uri).is_some() {
+                    self.publish(uri, Vec::new())?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// analyze the new text of a document and publish its diagnostics,
+    /// files that are neither toml nor yaml are ignored
+    fn update(&mut self, uri: Url, text: String) -> Result<(), LspError> {
+        let Some(format) = Format::from_path(Path::new(uri.path())) else {
+            return Ok(());
+        };
+        let document = Document::new(text, format);
+        let diagnostics = document
+            .diagnostics
+            .iter()
+            .map(|d| lsp_types::Diagnostic {
+                range: range(&document.src, d.span),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("abstaction".to_string()),
+                message: d.message.clone(),
+                ..Default::default()
+            })
+            .collect();
+        self.documents.insert(uri.clone(), document);
+        self.publish(uri, diagnostics)
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<lsp_types::Diagnostic>) -> Result<(), LspError> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        self.send(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            params,
+        ))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let at = params.text_document_position_params;
+        let document = self.documents.get(&at.text_document.uri)?;
+        let (span, text) = document.hover(position(&document.src, at.position))?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: text,
+            }),
+            range: Some(range(&document.src, span)),
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let at = params.text_document_position_params;
+        let document = self.documents.get(&at.text_document.uri)?;
+        let span = document.definition(position(&document.src, at.position))?;
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri: at.text_document.uri,
+            range: range(&document.src, span),
+        }))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let at = params.text_document_position;
+        let document = self.documents.get(&at.text_document.uri)?;
+        let items = document
+            .completions(position(&document.src, at.position))
+            .into_iter()
+            .map(|c| CompletionItem {
+                label: c.label,
+                kind: Some(CompletionItemKind::VARIABLE),
+                detail: Some(c.detail),
+                insert_text: Some(c.insert),
+                ..Default::default()
+            })
+            .collect();
+        Some(CompletionResponse::Array(items))
+    }
+}
+
+/// serve editors over stdin and stdout until they shut the server down
+pub fn run() -> Result<(), LspError> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["\"".to_string()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    // the connection is dropped before joining, so the writer thread ends
+    Server {
+        connection,
+        documents: HashMap::new(),
+    }
+    .serve()?;
+    io_threads.join()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_convert_between_utf16_units_and_characters() {
+        // the emoji takes two utf-16 units, one character
+        let src = "x = 1\na = \"😀x\"\n";
+        let cases = [(0, 1), (5, 6), (7, 7), (8, 8)];
+        for (units, column) in cases {
+            let pos = position(src, lsp_types::Position::new(1, units));
+            assert_eq!(pos, Position { line: 2, column }, "{}", units);
+            assert_eq!(lsp_position(src, pos), lsp_types::Position::new(1, units));
+        }
+        // within the emoji, the cursor is on it
+        assert_eq!(
+            position(src, lsp_types::Position::new(1, 6)),
+            Position { line: 2, column: 6 }
+        );
+
+        let span = Span {
+            start: Position { line: 2, column: 5 },
+            end: Position { line: 2, column: 9 },
+        };
+        assert_eq!(
+            range(src, span),
+            lsp_types::Range::new(
+                lsp_types::Position::new(1, 4),
+                lsp_types::Position::new(1, 9)
+            )
+        );
+    }
+}
@@ -1,7 +1,11 @@
+use std::process::ExitCode;
+
+use abstaction::cli::{self, Cli, Status};
 use abstaction::ui;
+use clap::Parser;
 use eframe::egui;
 
-fn main() -> Result<(), eframe::Error> {
+fn viewer() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([800.0, 400.0])
@@ -15,3 +19,17 @@ fn main() -> Result<(), eframe::Error> {
         Box::new(|cc| Box::new(ui::manager::Manager::new(cc))),
     )
 }
+
+fn main() -> ExitCode {
+    let status = match Cli::parse().command {
+        Some(command) => cli::run(command),
+        None => match viewer() {
+            Ok(()) => Status::Ok,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                Status::Error
+            }
+        },
+    };
+    status.into()
+}
@@ -0,0 +1,197 @@
+//! model file of the manager, with the recent files and the unsaved edits
+
+use std::path::{Path, PathBuf};
+
+use eframe::egui::{self, Color32};
+
+use crate::core::file::{self, LoadError};
+use crate::core::model::{Command, Model};
+
+/// number of recent files remembered
+const RECENT: usize = 8;
+
+/// key of the recent files in the storage of eframe
+pub const RECENT_KEY: &str = "recent files";
+
+#[derive(Debug, Clone, Default)]
+pub struct ModelPath {
+    /// file the model was loaded from or saved to, `None` for a new model
+    pub path: Option<PathBuf>,
+    /// recent files, the last opened or saved first
+    pub recent: Vec<PathBuf>,
+    /// edits of the model when it was loaded or saved
+    saved: Vec<Command>,
+}
+
+impl ModelPath {
+    /// the model has edits since it was loaded or saved, undoing them makes it clean again
+    pub fn is_dirty(&self, model: &Model) -> bool {
+        model.history.undo != self.saved
+    }
+
+    /// file name of the model, `untitled` for a new one
+    pub fn name(&self) -> String {
+        match &self.path {
+            Some(path) => path
+                .file_name()
+                .map_or(path.display().to_string(), |n| n.to_string_lossy().into()),
+            None => "untitled".to_string(),
+        }
+    }
+
+    /// name for the title of the window, marked while there are unsaved edits
+    pub fn title(&self, model: &Model) -> String {
+        match self.is_dirty(model) {
+            true => format!("{} *", self.name()),
+            false => self.name(),
+        }
+    }
+
+    fn remember(&mut self, path: &Path) {
+        self.recent.retain(|p| p != path);
+        self.recent.insert(0, path.to_path_buf());
+        self.recent.truncate(RECENT);
+    }
+
+    /// forget the file of the model, for a new one
+    pub fn new_model(&mut self, model: &Model) {
+        self.path = None;
+        self.saved = model.history.undo.clone();
+    }
+
+    pub fn open(&mut self, path: &Path) -> Result<Model, LoadError> {
+        let model = match file::read(path) {
+            Ok(model) => model,
+            Err(e) => {
+                // a file that cannot be read is no longer recent
+                if let LoadError::Io(_) = e {
+                    self.recent.retain(|p| p != path);
+                }
+                return Err(e);
+            }
+        };
+        self.path = Some(path.to_path_buf());
+        self.saved = model.history.undo.clone();
+        self.remember(path);
+        Ok(model)
+    }
+
+    pub fn save(&mut self, model: &Model, path: &Path) -> Result<(), LoadError> {
+        file::write(path, model)?;
+        self.path = Some(path.to_path_buf());
+        self.saved = model.history.undo.clone();
+        self.remember(path);
+        Ok(())
+    }
+}
+
+/// action waiting for a path typed by the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathAction {
+    Open,
+    SaveAs,
+}
+
+/// window asking for the path of a model file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPrompt {
+    pub action: PathAction,
+    pub text: String,
+    pub error: Option<String>,
+}
+
+impl PathPrompt {
+    pub fn new(action: PathAction, path: Option<&Path>) -> Self {
+        Self {
+            action,
+            text: path.map(|p| p.display().to_string()).unwrap_or_default(),
+            error: None,
+        }
+    }
+
+    /// show the window, the path once it is confirmed and `None` as long as it is open.
+    /// `open` is cleared when it is cancelled
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool) -> Option<PathBuf> {
+        let (title, button) = match self.action {
+            PathAction::Open => ("Open model", "Open"),
+            PathAction::SaveAs => ("Save model as", "Save"),
+        };
+        let mut path = None;
+        egui::Window::new(title)
+            .open(open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("path of a .toml, .yaml or .yml file");
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut self.text)
+                        .hint_text("models/plant.toml")
+                        .desired_width(300.0),
+                );
+                let enter = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                ui.separator();
+                if (ui.button(button).clicked() || enter) && !self.text.trim().is_empty() {
+                    path = Some(PathBuf::from(self.text.trim()));
+                }
+            });
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn undoing_every_edit_makes_the_model_clean() {
+        let mut file = ModelPath::default();
+        let mut model = Model::new();
+        file.new_model(&model);
+        assert_eq!(file.title(&model), "untitled");
+
+        model.add_fixed(0, 1);
+        assert!(file.is_dirty(&model));
+        assert_eq!(file.title(&model), "untitled *");
+        model.undo();
+        assert!(!file.is_dirty(&model));
+        model.redo();
+        assert!(file.is_dirty(&model));
+    }
+
+    #[test]
+    fn saved_and_opened_files_are_recent() {
+        let dir = std::env::temp_dir().join(format!("abstaction-recent-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = |i: usize| dir.join(format!("model{}.toml", i));
+
+        let mut file = ModelPath::default();
+        let mut model = Model::new();
+        model.add_fixed(0, 1);
+        for i in 0..RECENT + 2 {
+            file.save(&model, &path(i)).unwrap();
+        }
+        assert!(!file.is_dirty(&model));
+        assert_eq!(file.title(&model), format!("model{}.toml", RECENT + 1));
+        assert_eq!(file.recent.len(), RECENT);
+        assert_eq!(file.recent[0], path(RECENT + 1));
+
+        // an opened file moves to the front once
+        let opened = file.open(&path(5)).unwrap();
+        assert_eq!(opened.constraints(), model.constraints());
+        assert_eq!(file.recent[0], path(5));
+        assert_eq!(file.recent.iter().filter(|p| **p == path(5)).count(), 1);
+
+        // a file which is gone is forgotten
+        fs::remove_file(path(4)).unwrap();
+        assert!(file.open(&path(4)).is_err());
+        assert!(!file.recent.contains(&path(4)));
+        assert_eq!(file.path, Some(path(5)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,534 @@
+//! forms adding constraints to the model of the manager
+
+use eframe::egui::{self, Color32};
+
+use crate::core::model::{
+    Boundary, Compare, Constraint, ConstraintId, DoubleConstrain, Fixed, Limit, Model,
+    SingleConstrain,
+};
+use crate::core::statement::Statement;
+
+const COMPARES: [Compare; 5] = [
+    Compare::LT,
+    Compare::LTE,
+    Compare::GT,
+    Compare::GTE,
+    Compare::EQ,
+];
+
+fn integer(label: &str, text: &str) -> Result<i64, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("{} `{}` is not an integer", label, text.trim()))
+}
+
+/// variable given by its name or id, a new name gets a fresh id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VarField {
+    pub text: String,
+}
+
+impl VarField {
+    /// id of the variable and its name if it is new, ids in `taken` are not fresh
+    fn resolve(
+        &self,
+        label: &str,
+        model: &Model,
+        taken: &[usize],
+    ) -> Result<(usize, Option<String>), String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Err(format!("choose the {} variable", label));
+        }
+        if let Some(id) = model.lookup(text) {
+            return Ok((id, None));
+        }
+
+        let mut chars = text.chars();
+        let is_name = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_');
+        if !is_name {
+            return Err(format!("`{}` is neither a variable nor a name", text));
+        }
+        let fresh = model
+            .variables()
+            .into_iter()
+            .chain(taken.iter().copied())
+            .max()
+            .map_or(0, |id| id + 1);
+        Ok((fresh, Some(text.to_string())))
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, label: &str, model: &Model) {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(
+                egui::TextEdit::singleline(&mut self.text)
+                    .hint_text("name or #id")
+                    .desired_width(120.0),
+            );
+            ui.menu_button("▼", |ui| {
+                for id in model.variables() {
+                    let text = match model.name(id) {
+                        Some(name) => name.to_string(),
+                        None => format!("#{}", id),
+                    };
+                    if ui.button(&text).clicked() {
+                        self.text = text;
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    }
+}
+
+/// optional limit of a boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitField {
+    pub enabled: bool,
+    pub point: String,
+    pub inclusive: bool,
+}
+
+impl LimitField {
+    fn new(point: &str, inclusive: bool) -> Self {
+        Self {
+            enabled: true,
+            point: point.to_string(),
+            inclusive,
+        }
+    }
+
+    fn limit(&self, label: &str) -> Result<Option<Limit<i64>>, String> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        Ok(Some(Limit {
+            point: integer(label, &self.point)?,
+            equal: self.inclusive,
+        }))
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, label: &str) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.enabled, label);
+            ui.add_enabled_ui(self.enabled, |ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.point).desired_width(80.0));
+                ui.checkbox(&mut self.inclusive, "inclusive");
+            });
+        });
+    }
+}
+
+/// fields of one kind of constraint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintForm {
+    Boundary {
+        var: VarField,
+        bot: LimitField,
+        top: LimitField,
+    },
+    Fixed {
+        var: VarField,
+        value: String,
+    },
+    /// `mul * left + off cmp right`
+    Relation {
+        left: VarField,
+        right: VarField,
+        mul: String,
+        off: String,
+        cmp: Compare,
+    },
+}
+
+impl ConstraintForm {
+    pub fn boundary() -> Self {
+        Self::Boundary {
+            var: VarField::default(),
+            bot: LimitField::new("0", true),
+            top: LimitField::new("10", false),
+        }
+    }
+
+    pub fn fixed() -> Self {
+        Self::Fixed {
+            var: VarField::default(),
+            value: "0".to_string(),
+        }
+    }
+
+    pub fn relation() -> Self {
+        Self::Relation {
+            left: VarField::default(),
+            right: VarField::default(),
+            mul: "1".to_string(),
+            off: "0".to_string(),
+            cmp: Compare::LTE,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ConstraintForm::Boundary { .. } => "Add boundary",
+            ConstraintForm::Fixed { .. } => "Add fixed value",
+            ConstraintForm::Relation { .. } => "Add relation",
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, model: &Model) {
+        match self {
+            ConstraintForm::Boundary { var, bot, top } => {
+                var.ui(ui, "variable", model);
+                bot.ui(ui, "bottom");
+                top.ui(ui, "top");
+            }
+            ConstraintForm::Fixed { var, value } => {
+                var.ui(ui, "variable", model);
+                ui.horizontal(|ui| {
+                    ui.label("value");
+                    ui.add(egui::TextEdit::singleline(value).desired_width(80.0));
+                });
+            }
+            ConstraintForm::Relation {
+                left,
+                right,
+                mul,
+                off,
+                cmp,
+            } => {
+                left.ui(ui, "left", model);
+                right.ui(ui, "right", model);
+                ui.horizontal(|ui| {
+                    ui.label("multiplier");
+                    ui.add(egui::TextEdit::singleline(mul).desired_width(60.0));
+                    ui.label("offset");
+                    ui.add(egui::TextEdit::singleline(off).desired_width(60.0));
+                });
+                egui::ComboBox::from_label("comparison")
+                    .selected_text(cmp.symbol())
+                    .show_ui(ui, |ui| {
+                        for c in COMPARES {
+                            ui.selectable_value(cmp, c, c.symbol());
+                        }
+                    });
+                ui.label(format!(
+                    "{} * {} + {} {} {}",
+                    mul.trim(),
+                    left.text.trim(),
+                    off.trim(),
+                    cmp.symbol(),
+                    right.text.trim()
+                ));
+            }
+        }
+    }
+
+    /// form holding the fields of `constraint`
+    pub fn edit(constraint: &Constraint, model: &Model) -> Self {
+        let var = |id: usize| VarField {
+            text: match model.name(id) {
+                Some(name) => name.to_string(),
+                None => format!("#{}", id),
+            },
+        };
+        let limit = |limit: Option<Limit<i64>>| match limit {
+            Some(limit) => LimitField::new(&limit.point.to_string(), limit.equal),
+            None => LimitField {
+                enabled: false,
+                ..LimitField::new("0", true)
+            },
+        };
+        match constraint {
+            Constraint::Single(SingleConstrain::Boundary(boundary)) => Self::Boundary {
+                var: var(boundary.id()),
+                bot: limit(boundary.bot.map(|b| b.limit)),
+                top: limit(boundary.top.map(|t| t.limit)),
+            },
+            Constraint::Single(SingleConstrain::Fixed(fixed)) => Self::Fixed {
+                var: var(fixed.id),
+                value: fixed.value.to_string(),
+            },
+            Constraint::Double(DoubleConstrain::LinearRelation(linear)) => Self::Relation {
+                left: var(linear.left()),
+                right: var(linear.right()),
+                mul: linear.mul().unwrap_or(1).to_string(),
+                off: linear.off().unwrap_or(0).to_string(),
+                cmp: linear.cmp(),
+            },
+        }
+    }
+
+    /// the constraint of the fields, with the names of its new variables
+    fn build(&self, model: &Model) -> Result<(Constraint, Vec<(usize, String)>), String> {
+        match self {
+            ConstraintForm::Boundary { var, bot, top } => {
+                let (id, name) = var.resolve("constrained", model, &[])?;
+                let (bot, top) = (bot.limit("bottom")?, top.limit("top")?);
+                if bot.is_none() && top.is_none() {
+                    return Err("set a bottom or a top limit".to_string());
+                }
+                let boundary = Boundary::create(id, top, bot).map_err(|e| e.to_string())?;
+                Ok((
+                    Constraint::Single(SingleConstrain::Boundary(boundary)),
+                    name.map(|name| (id, name)).into_iter().collect(),
+                ))
+            }
+            ConstraintForm::Fixed { var, value } => {
+                let (id, name) = var.resolve("constrained", model, &[])?;
+                let fixed = Fixed::new(id, integer("value", value)?);
+                Ok((
+                    Constraint::Single(SingleConstrain::Fixed(fixed)),
+                    name.map(|name| (id, name)).into_iter().collect(),
+                ))
+            }
+            ConstraintForm::Relation {
+                left,
+                right,
+                mul,
+                off,
+                cmp,
+            } => {
+                let (l, l_name) = left.resolve("left", model, &[])?;
+                let (r, r_name) = match right.text.trim() == left.text.trim() {
+                    true => (l, None),
+                    false => right.resolve("right", model, &[l])?,
+                };
+                let (mul, off) = (integer("multiplier", mul)?, integer("offset", off)?);
+                // the statement puts the variables in order of their ids
+                let src = format!(
+                    "{}{} * #{} {} {} {} #{}",
+                    if mul < 0 { "-" } else { "" },
+                    mul.unsigned_abs(),
+                    l,
+                    if off < 0 { "-" } else { "+" },
+                    off.unsigned_abs(),
+                    cmp.symbol(),
+                    r
+                );
+                let statement = Statement::parse(model, &src).map_err(|e| e.to_string())?;
+                let names = [(l, l_name), (r, r_name)]
+                    .into_iter()
+                    .filter_map(|(id, name)| Some((id, name?)))
+                    .collect();
+                Ok((statement.constraint, names))
+            }
+        }
+    }
+
+    /// add the constraint to `model`, or replace `cid` by it, and name its new variables
+    pub fn submit(
+        &self,
+        model: &mut Model,
+        cid: Option<ConstraintId>,
+    ) -> Result<ConstraintId, String> {
+        let (constraint, names) = self.build(model)?;
+        let cid = match cid {
+            Some(cid) => {
+                model
+                    .replace(cid, constraint)
+                    .ok_or_else(|| format!("{} was removed", cid))?;
+                cid
+            }
+            None => model.add(constraint).map_err(|e| e.to_string())?,
+        };
+        for (id, name) in names {
+            model.set_name(id, name);
+        }
+        Ok(cid)
+    }
+}
+
+/// window of a form, with the error of the last try to add it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddConstraint {
+    pub form: ConstraintForm,
+    pub error: Option<String>,
+}
+
+impl AddConstraint {
+    pub fn new(form: ConstraintForm) -> Self {
+        Self { form, error: None }
+    }
+
+    /// show the window, false once it is closed
+    pub fn show(&mut self, ctx: &egui::Context, model: &mut Model) -> bool {
+        let mut open = true;
+        let mut done = false;
+        egui::Window::new(self.form.title())
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                self.form.ui(ui, model);
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Add").clicked() {
+                        match self.form.submit(model, None) {
+                            Ok(_) => done = true,
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        done = true;
+                    }
+                });
+            });
+        open && !done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::model::Assignment;
+
+    use super::*;
+
+    fn var(text: &str) -> VarField {
+        VarField {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn forms_add_constraints_and_name_new_variables() {
+        let mut model = Model::new();
+        let ConstraintForm::Boundary { bot, top, .. } = ConstraintForm::boundary() else {
+            unreachable!()
+        };
+        let boundary = ConstraintForm::Boundary {
+            var: var("x"),
+            bot,
+            top,
+        };
+        boundary.submit(&mut model, None).unwrap();
+        assert_eq!(model.lookup("x"), Some(0));
+
+        let fixed = ConstraintForm::Fixed {
+            var: var("x"),
+            value: " 4 ".to_string(),
+        };
+        fixed.submit(&mut model, None).unwrap();
+        assert_eq!(model.variables(), [0].into());
+
+        // y + 1 < x, with y new and above x
+        let relation = ConstraintForm::Relation {
+            left: var("y"),
+            right: var("#0"),
+            mul: "1".to_string(),
+            off: "1".to_string(),
+            cmp: Compare::LT,
+        };
+        let cid = relation.submit(&mut model, None).unwrap();
+        assert_eq!(model.lookup("y"), Some(1));
+        let mut relation = Model::new();
+        relation.add(model.get(cid).unwrap()).unwrap();
+        let holds = |x, y| relation.check(&Assignment::from([(0, x), (1, y)])).is_ok();
+        assert!(holds(4, 2));
+        assert!(!holds(4, 3));
+
+        // the greater id can not be scaled
+        let relation = ConstraintForm::Relation {
+            left: var("y"),
+            right: var("x"),
+            mul: "2".to_string(),
+            off: "0".to_string(),
+            cmp: Compare::LT,
+        };
+        assert!(relation.submit(&mut model, None).is_err());
+    }
+
+    #[test]
+    fn invalid_fields_are_reported() {
+        let mut model = Model::new();
+        let error = |form: ConstraintForm, model: &mut Model| form.submit(model, None).unwrap_err();
+
+        let fixed = |var_text: &str, value: &str| ConstraintForm::Fixed {
+            var: var(var_text),
+            value: value.to_string(),
+        };
+        assert_eq!(
+            error(fixed(" ", "1"), &mut model),
+            "choose the constrained variable"
+        );
+        assert_eq!(
+            error(fixed("1x", "1"), &mut model),
+            "`1x` is neither a variable nor a name"
+        );
+        assert_eq!(
+            error(fixed("x", "a"), &mut model),
+            "value `a` is not an integer"
+        );
+
+        let limit = |enabled, point: &str| LimitField {
+            enabled,
+            ..LimitField::new(point, true)
+        };
+        let boundary = |bot, top| ConstraintForm::Boundary {
+            var: var("x"),
+            bot,
+            top,
+        };
+        assert_eq!(
+            error(boundary(limit(false, "0"), limit(false, "0")), &mut model),
+            "set a bottom or a top limit"
+        );
+        assert_eq!(
+            error(boundary(limit(true, "5"), limit(true, "")), &mut model),
+            "top `` is not an integer"
+        );
+        assert!(boundary(limit(true, "5"), limit(true, "3"))
+            .submit(&mut model, None)
+            .is_err());
+        // nothing was added, not even a name
+        assert!(model.constraints().is_empty());
+        assert_eq!(model.lookup("x"), None);
+    }
+
+    #[test]
+    fn edits_start_from_the_constraint_and_replace_it() {
+        let mut model = Model::new();
+        model.set_name(0, "x".to_string());
+        let boundary = model
+            .add_boundary(
+                0,
+                None,
+                Some(Limit {
+                    point: 2,
+                    equal: false,
+                }),
+            )
+            .unwrap();
+        let relation = model.add_linear(0, 3, Some(-2), Some(5), Compare::GTE);
+
+        for cid in [boundary, relation] {
+            let constraint = model.get(cid).unwrap();
+            let form = ConstraintForm::edit(&constraint, &model);
+            assert_eq!(form.submit(&mut model, Some(cid)), Ok(cid));
+            assert_eq!(model.get(cid), Some(constraint));
+        }
+
+        let ConstraintForm::Boundary { var, bot, mut top } =
+            ConstraintForm::edit(&model.get(boundary).unwrap(), &model)
+        else {
+            panic!("a boundary is edited in a boundary form");
+        };
+        assert_eq!(var.text, "x");
+        assert!(!top.enabled);
+        top.enabled = true;
+        top.point = "9".to_string();
+        let form = ConstraintForm::Boundary { var, bot, top };
+        form.submit(&mut model, Some(boundary)).unwrap();
+        assert_eq!(model.describe(&model.get(boundary).unwrap()), "2 < x <= 9");
+
+        model.remove(boundary);
+        assert_eq!(
+            form.submit(&mut model, Some(boundary)),
+            Err(format!("{} was removed", boundary))
+        );
+    }
+}
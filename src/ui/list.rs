@@ -0,0 +1,108 @@
+//! constraints of the model grouped by variable, edited in place
+
+use eframe::egui::{self, Color32};
+
+use super::forms::ConstraintForm;
+use crate::core::model::{ConstraintId, Model};
+
+/// constraint being edited, under the group of `var`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    cid: ConstraintId,
+    var: usize,
+    form: ConstraintForm,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstraintList {
+    edit: Option<Edit>,
+}
+
+impl ConstraintList {
+    pub fn ui(&mut self, ui: &mut egui::Ui, model: &mut Model) {
+        let constraints = model.constraints();
+        if constraints.is_empty() {
+            ui.label("no constraints yet");
+        }
+        // an edit of a constraint removed by undo is dropped
+        if let Some(edit) = &self.edit {
+            if model.get(edit.cid).is_none() {
+                self.edit = None;
+            }
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for var in model.variables() {
+                let title = match model.name(var) {
+                    Some(name) => format!("{} ( #{} )", name, var),
+                    None => format!("#{}", var),
+                };
+                egui::CollapsingHeader::new(title)
+                    .id_source(("variable", var))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for (cid, constraint) in constraints.iter() {
+                            if constraint.involves(var) {
+                                self.row(ui, model, var, *cid);
+                            }
+                        }
+                    });
+            }
+        });
+    }
+
+    /// one constraint, or its form while it is edited
+    fn row(&mut self, ui: &mut egui::Ui, model: &mut Model, var: usize, cid: ConstraintId) {
+        let Some(constraint) = model.get(cid) else {
+            return;
+        };
+
+        match &mut self.edit {
+            Some(edit) if edit.cid == cid && edit.var == var => {
+                let mut done = false;
+                ui.group(|ui| {
+                    ui.label(cid.to_string());
+                    edit.form.ui(ui, model);
+                    if let Some(error) = &edit.error {
+                        ui.colored_label(Color32::RED, error);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Apply").clicked() {
+                            match edit.form.submit(model, Some(cid)) {
+                                Ok(_) => done = true,
+                                Err(e) => edit.error = Some(e),
+                            }
+                        }
+                        if ui.button("Cancel").clicked() {
+                            done = true;
+                        }
+                    });
+                });
+                if done {
+                    self.edit = None;
+                }
+            }
+            _ => {
+                ui.horizontal(|ui| {
+                    let mut text = format!("{}: {}", cid, model.describe(&constraint));
+                    if let Some(soft) = model.soft_of(cid) {
+                        text.push_str(&format!(" ( soft, weight {} )", soft.weight));
+                    }
+                    ui.label(text);
+                    if ui.small_button("✏").on_hover_text("edit").clicked() {
+                        self.edit = Some(Edit {
+                            cid,
+                            var,
+                            form: ConstraintForm::edit(&constraint, model),
+                            error: None,
+                        });
+                    }
+                    if ui.small_button("🗑").on_hover_text("delete").clicked() {
+                        model.remove(cid);
+                    }
+                });
+            }
+        }
+    }
+}
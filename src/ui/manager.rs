@@ -1,34 +1,66 @@
-use eframe::{
-    egui::{self, Color32, Stroke},
-    emath::Numeric,
-};
-use egui_plot::{
-    HLine, Legend, Line, LineStyle, PlotItem, PlotPoint, PlotPoints, Points, Polygon, VLine,
-};
+use std::path::PathBuf;
 
-use crate::core::model::{Limit, Model, SingleConstrain};
+use eframe::egui;
+use eframe::egui::{Color32, Key, KeyboardShortcut, Modifiers};
+
+use super::file::{ModelPath, PathAction, PathPrompt, RECENT_KEY};
+use super::forms::{AddConstraint, ConstraintForm};
+use super::list::ConstraintList;
+use super::plot::RegionPlot;
+use crate::core::model::Model;
+
+const UNDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+const REDO_SHORTCUT_ALT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+const NEW_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::N);
+const OPEN_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+const SAVE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
+const SAVE_AS_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::S);
+
+/// action dropping the model, held back while it has unsaved edits
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pending {
+    New,
+    /// open the file, or ask for its path
+    Open(Option<PathBuf>),
+    Quit,
+}
 
 pub struct Manager {
     model: Model,
+    /// feasible region of two variables
+    plot: RegionPlot,
+    /// open form adding a constraint
+    dialog: Option<AddConstraint>,
+    list: ConstraintList,
+    file: ModelPath,
+    /// open window asking for a path
+    prompt: Option<PathPrompt>,
+    /// action waiting for the unsaved edits to be saved or discarded
+    pending: Option<Pending>,
+    /// the window closes even with unsaved edits
+    quitting: bool,
+    /// last error of a file operation
+    error: Option<String>,
+    title: String,
 }
 
 impl Default for Manager {
     fn default() -> Self {
-        let mut model = Model::new();
-        model.add_fixed(0, 10);
-        model.add_boundary(
-            0,
-            Some(Limit {
-                point: 20,
-                equal: false,
-            }),
-            Some(Limit {
-                point: 0,
-                equal: true,
-            }),
-        );
-
-        Self { model }
+        Self {
+            model: Model::new(),
+            plot: RegionPlot::new(0, 1),
+            dialog: None,
+            list: ConstraintList::default(),
+            file: ModelPath::default(),
+            prompt: None,
+            pending: None,
+            quitting: false,
+            error: None,
+            title: String::new(),
+        }
     }
 }
 
@@ -38,20 +70,262 @@ impl Manager {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        Default::default()
+        let mut manager = Self::default();
+        if let Some(storage) = cc.storage {
+            manager.file.recent = eframe::get_value(storage, RECENT_KEY).unwrap_or_default();
+        }
+        manager
+    }
+
+    /// show `model` from the start, on its first two variables
+    fn replace_model(&mut self, model: Model) {
+        let mut ids = model.variables().into_iter();
+        let x = ids.next().unwrap_or(0);
+        let y = ids.next().unwrap_or(x + 1);
+        self.model = model;
+        self.plot = RegionPlot::new(x, y);
+        self.list = ConstraintList::default();
+        self.dialog = None;
+    }
+
+    /// run `pending` now, or once the unsaved edits are saved or discarded
+    fn request(&mut self, ctx: &egui::Context, pending: Pending) {
+        match self.file.is_dirty(&self.model) {
+            true => self.pending = Some(pending),
+            false => self.run(ctx, pending),
+        }
+    }
+
+    fn run(&mut self, ctx: &egui::Context, pending: Pending) {
+        match pending {
+            Pending::New => {
+                self.replace_model(Model::new());
+                self.file.new_model(&self.model);
+            }
+            Pending::Open(Some(path)) => {
+                if let Err(e) = self.open(path) {
+                    self.error = Some(e);
+                }
+            }
+            Pending::Open(None) => {
+                let path = self.file.path.as_deref();
+                self.prompt = Some(PathPrompt::new(PathAction::Open, path));
+            }
+            Pending::Quit => {
+                self.quitting = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    fn open(&mut self, path: PathBuf) -> Result<(), String> {
+        let model = self
+            .file
+            .open(&path)
+            .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+        self.replace_model(model);
+        Ok(())
+    }
+
+    /// save to the file of the model, false if it has none yet and its path is asked for
+    fn save(&mut self) -> bool {
+        let Some(path) = self.file.path.clone() else {
+            self.prompt = Some(PathPrompt::new(PathAction::SaveAs, None));
+            return false;
+        };
+        match self.file.save(&self.model, &path) {
+            Ok(()) => true,
+            Err(e) => {
+                self.error = Some(format!("cannot save {}: {}", path.display(), e));
+                false
+            }
+        }
+    }
+
+    fn file_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let button =
+            |text, shortcut| egui::Button::new(text).shortcut_text(ctx.format_shortcut(shortcut));
+        if ui.add(button("New", &NEW_SHORTCUT)).clicked() {
+            self.request(ctx, Pending::New);
+            ui.close_menu();
+        }
+        if ui.add(button("Open…", &OPEN_SHORTCUT)).clicked() {
+            self.request(ctx, Pending::Open(None));
+            ui.close_menu();
+        }
+        ui.add_enabled_ui(!self.file.recent.is_empty(), |ui| {
+            ui.menu_button("Open Recent", |ui| {
+                for path in self.file.recent.clone() {
+                    if ui.button(path.display().to_string()).clicked() {
+                        self.request(ctx, Pending::Open(Some(path)));
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        ui.separator();
+        if ui.add(button("Save", &SAVE_SHORTCUT)).clicked() {
+            self.save();
+            ui.close_menu();
+        }
+        if ui.add(button("Save As…", &SAVE_AS_SHORTCUT)).clicked() {
+            let path = self.file.path.as_deref();
+            self.prompt = Some(PathPrompt::new(PathAction::SaveAs, path));
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("Quit").clicked() {
+            self.request(ctx, Pending::Quit);
+            ui.close_menu();
+        }
+    }
+
+    fn file_windows(&mut self, ctx: &egui::Context) {
+        if let Some(prompt) = &mut self.prompt {
+            let mut open = true;
+            let path = prompt.show(ctx, &mut open);
+            let action = prompt.action;
+            let done = match path {
+                Some(path) => {
+                    let result = match action {
+                        PathAction::Open => self.open(path),
+                        PathAction::SaveAs => self
+                            .file
+                            .save(&self.model, &path)
+                            .map_err(|e| format!("cannot save {}: {}", path.display(), e)),
+                    };
+                    if let (Err(e), Some(prompt)) = (&result, &mut self.prompt) {
+                        prompt.error = Some(e.clone());
+                    }
+                    result.is_ok()
+                }
+                None => false,
+            };
+            if done {
+                self.prompt = None;
+                // the edits held back by a quit or an other file are saved now
+                if let (PathAction::SaveAs, Some(pending)) = (action, self.pending.take()) {
+                    self.run(ctx, pending);
+                }
+            } else if !open {
+                self.prompt = None;
+                self.pending = None;
+            }
+        }
+
+        if self.prompt.is_some() {
+            return;
+        }
+        let Some(pending) = self.pending.clone() else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} has unsaved edits, save them first?",
+                    self.file.name()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending = None;
+                    }
+                });
+            });
+        match choice {
+            // without a path, the pending action waits for the save as prompt
+            Some(true) if self.save() => {
+                self.pending = None;
+                self.run(ctx, pending);
+            }
+            Some(false) => {
+                self.pending = None;
+                self.run(ctx, pending);
+            }
+            Some(true) | None => {}
+        }
     }
 }
 
 impl eframe::App for Manager {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, RECENT_KEY, &self.file.recent);
+    }
+
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // closing the window asks about unsaved edits like quitting does
+        if ctx.input(|i| i.viewport().close_requested())
+            && !self.quitting
+            && self.file.is_dirty(&self.model)
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.pending = Some(Pending::Quit);
+        }
+        let title = format!("viewer - {}", self.file.title(&self.model));
+        if title != self.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
+
+        // shift is ignored by the plain shortcuts, so the ones with shift are consumed first
+        let save_as = ctx.input_mut(|i| i.consume_shortcut(&SAVE_AS_SHORTCUT));
+        if save_as {
+            let path = self.file.path.as_deref();
+            self.prompt = Some(PathPrompt::new(PathAction::SaveAs, path));
+        } else if ctx.input_mut(|i| i.consume_shortcut(&SAVE_SHORTCUT)) {
+            self.save();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&NEW_SHORTCUT)) {
+            self.request(ctx, Pending::New);
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&OPEN_SHORTCUT)) {
+            self.request(ctx, Pending::Open(None));
+        }
+
+        // shift is ignored by the undo shortcut, so redo is consumed first
+        let redo = ctx.input_mut(|i| {
+            i.consume_shortcut(&REDO_SHORTCUT) || i.consume_shortcut(&REDO_SHORTCUT_ALT)
+        });
+        let undo = ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT));
+        if redo {
+            self.model.redo();
+        } else if undo {
+            self.model.undo();
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
 
             egui::menu::bar(ui, |ui| {
-                ui.menu_button("Menu", |ui| {
-                    if ui.button("Quit").clicked() {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                ui.menu_button("File", |ui| self.file_menu(ctx, ui));
+                ui.menu_button("Edit", |ui| {
+                    let undo = egui::Button::new("Undo")
+                        .shortcut_text(ctx.format_shortcut(&UNDO_SHORTCUT));
+                    if ui
+                        .add_enabled(self.model.history.can_undo(), undo)
+                        .clicked()
+                    {
+                        self.model.undo();
+                        ui.close_menu();
+                    }
+                    let redo = egui::Button::new("Redo")
+                        .shortcut_text(ctx.format_shortcut(&REDO_SHORTCUT));
+                    if ui
+                        .add_enabled(self.model.history.can_redo(), redo)
+                        .clicked()
+                    {
+                        self.model.redo();
+                        ui.close_menu();
                     }
                 });
                 ui.add_space(16.0);
@@ -60,55 +334,46 @@ impl eframe::App for Manager {
             });
         });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
+        egui::SidePanel::right("side bar").show(ctx, |ui| {
             ui.separator();
-            egui_plot::Plot::new("plot..")
-                .width(600.0)
-                .legend(Legend::default())
-                .show(ui, |plot_ui| {
-                    // plot_ui.polygon(
-                    //     Polygon::new(vec![[0.0, 0.0], [5.0, 5.0], [5.0, 1.0]])
-                    //         .style(egui_plot::LineStyle::Dashed { length: 12.0 })
-                    //         .stroke(Stroke::new(3.0, Color32::from_rgb(100, 100, 200)))
-                    //         .fill_color(Color32::from_rgb(200, 200, 200)),
-                    // );
-
-                    for (id, sc_vec) in self.model.single.iter() {
-                        for sc in sc_vec {
-                            match sc {
-                                SingleConstrain::Fixed(f) => plot_ui.hline(
-                                    HLine::new(f.value.to_f64())
-                                        .name(format!("Fixed : {:?}", f))
-                                        .width(3.0),
-                                ),
-                                SingleConstrain::Boundary(b) => {
-                                    if let Some(bot) = b.bot {
-                                        plot_ui.hline(
-                                            HLine::new(bot.limit.point.to_f64())
-                                                .style(match bot.limit.equal {
-                                                    true => LineStyle::Dotted { spacing: 10.0 },
-                                                    false => LineStyle::Solid,
-                                                })
-                                                .name(format!("Bottom : {:?}", bot.limit.point))
-                                                .width(3.0),
-                                        );
-                                    }
-                                    if let Some(top) = b.top {
-                                        plot_ui.hline(
-                                            HLine::new(top.limit.point.to_f64())
-                                                .style(match top.limit.equal {
-                                                    true => LineStyle::Dotted { spacing: 10.0 },
-                                                    false => LineStyle::Solid,
-                                                })
-                                                .name(format!("Top : {:?}", top.limit.point))
-                                                .width(3.0),
-                                        );
-                                    }
-                                }
-                            }
-                        }
+            ui.menu_button("add constraint", |ui| {
+                let form = if ui.button("Boundary").clicked() {
+                    Some(ConstraintForm::boundary())
+                } else if ui.button("Fixed").clicked() {
+                    Some(ConstraintForm::fixed())
+                } else if ui.button("Relation").clicked() {
+                    Some(ConstraintForm::relation())
+                } else {
+                    None
+                };
+                if let Some(form) = form {
+                    self.dialog = Some(AddConstraint::new(form));
+                    ui.close_menu();
+                }
+            });
+        });
+
+        if let Some(error) = self.error.clone() {
+            egui::TopBottomPanel::bottom("error").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::RED, error);
+                    if ui.small_button("dismiss").clicked() {
+                        self.error = None;
                     }
                 });
+            });
+        }
+
+        egui::SidePanel::left("constraints").show(ctx, |ui| {
+            ui.heading("Constraints");
+            ui.separator();
+            self.list.ui(ui, &mut self.model);
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.plot.axes_ui(ui, &self.model);
+            ui.separator();
+            self.plot.show(ui, &mut self.model);
 
             ui.separator();
 
@@ -117,15 +382,11 @@ impl eframe::App for Manager {
             });
         });
 
-        egui::SidePanel::right("side bar").show(ctx, |ui| {
-            ui.separator();
-            ui.menu_button("add constraint", |ui| {
-                if ui.button("Boundary").clicked() {}
-                if ui.button("Fixed").clicked() {}
-                if ui.button("Relation").clicked() {}
-            });
-
-            if ui.button("Open Plot").clicked() {}
-        });
+        if let Some(dialog) = &mut self.dialog {
+            if !dialog.show(ctx, &mut self.model) {
+                self.dialog = None;
+            }
+        }
+        self.file_windows(ctx);
     }
 }
@@ -1,2 +1,5 @@
+mod file;
+mod forms;
+mod list;
 pub mod manager;
 mod plot;
@@ -0,0 +1,744 @@
+//! feasible region of two variables of a `Model`, one on each axis
+//!
+//! boundaries and fixed values become vertical or horizontal edges, linear relations between
+//! both variables become sloped ones. the region satisfying every hard constraint on the two
+//! variables is shaded, constraints involving other variables are left out.
+//!
+//! the limits of boundaries and fixed values can be dragged, they snap to integers and are
+//! stored once released. a click places a probe point, green if it satisfies every hard
+//! constraint on both variables and red otherwise. violated soft ones are listed with their
+//! penalties, like the shading they do not decide the colour.
+
+use std::collections::HashMap;
+
+use eframe::egui::{self, Align2, Color32, CursorIcon, Response, Vec2b};
+use egui_plot::{
+    Legend, Line, LineStyle, MarkerShape, Plot, PlotBounds, PlotPoint, PlotPoints, Points, Polygon,
+    Text,
+};
+
+use crate::core::model::{
+    Assignment, CheckReport, Compare, Constraint, ConstraintId, Domain, DoubleConstrain, Fixed,
+    Limit, Model, SingleConstrain,
+};
+
+/// distance in points within which a line can be grabbed
+const GRAB_DISTANCE: f32 = 6.0;
+
+/// `a * x + b * y <= c`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HalfPlane {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl HalfPlane {
+    /// how far `p` is outside, negative inside
+    fn excess(&self, p: [f64; 2]) -> f64 {
+        self.a * p[0] + self.b * p[1] - self.c
+    }
+
+    /// the other side of the same line
+    fn opposite(&self) -> Self {
+        Self {
+            a: -self.a,
+            b: -self.b,
+            c: -self.c,
+        }
+    }
+
+    /// part of a convex polygon within the half-plane
+    pub fn clip(&self, polygon: &[[f64; 2]]) -> Vec<[f64; 2]> {
+        let mut clipped = Vec::new();
+        for (i, p) in polygon.iter().enumerate() {
+            let q = polygon[(i + 1) % polygon.len()];
+            let (ep, eq) = (self.excess(*p), self.excess(q));
+            if ep <= 0.0 {
+                clipped.push(*p);
+            }
+            if (ep < 0.0 && eq > 0.0) || (ep > 0.0 && eq < 0.0) {
+                let t = ep / (ep - eq);
+                clipped.push([p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])]);
+            }
+        }
+        clipped
+    }
+
+    /// piece of the line `a * x + b * y = c` within a convex polygon
+    pub fn segment(&self, polygon: &[[f64; 2]]) -> Option<[[f64; 2]; 2]> {
+        let mut points = Vec::new();
+        for (i, p) in polygon.iter().enumerate() {
+            let q = polygon[(i + 1) % polygon.len()];
+            let (ep, eq) = (self.excess(*p), self.excess(q));
+            if ep == 0.0 {
+                points.push(*p);
+            } else if (ep < 0.0) != (eq < 0.0) && eq != 0.0 {
+                let t = ep / (ep - eq);
+                points.push([p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])]);
+            }
+        }
+        Some([*points.first()?, *points.last()?])
+    }
+}
+
+/// one side of a constraint drawn on the plot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Edge {
+    pub plane: HalfPlane,
+    /// points on the line satisfy the constraint
+    pub inclusive: bool,
+    /// the constraint holds on the line only ( fixed values and `=` relations )
+    pub line_only: bool,
+}
+
+/// edges of a constraint on `x` and `y`, none if it involves another variable
+pub fn edges(constraint: &Constraint, x: usize, y: usize) -> Vec<Edge> {
+    // ( a, b ) of the variable `id`
+    let axis = |id: usize| match id {
+        id if id == x => Some((1.0, 0.0)),
+        id if id == y => Some((0.0, 1.0)),
+        _ => None,
+    };
+    let edge = |(a, b): (f64, f64), c: f64, inclusive: bool, line_only: bool| Edge {
+        plane: HalfPlane { a, b, c },
+        inclusive,
+        line_only,
+    };
+
+    match constraint {
+        Constraint::Single(SingleConstrain::Boundary(boundary)) => {
+            let Some((a, b)) = axis(boundary.id()) else {
+                return Vec::new();
+            };
+            let mut edges = Vec::new();
+            if let Some(bot) = boundary.bot {
+                let point = bot.limit.point as f64;
+                edges.push(edge((-a, -b), -point, bot.limit.equal, false));
+            }
+            if let Some(top) = boundary.top {
+                let point = top.limit.point as f64;
+                edges.push(edge((a, b), point, top.limit.equal, false));
+            }
+            edges
+        }
+        Constraint::Single(SingleConstrain::Fixed(fixed)) => match axis(fixed.id) {
+            Some(ab) => vec![edge(ab, fixed.value as f64, true, true)],
+            None => Vec::new(),
+        },
+        Constraint::Double(DoubleConstrain::LinearRelation(linear)) => {
+            let (Some(left), Some(right)) = (axis(linear.left()), axis(linear.right())) else {
+                return Vec::new();
+            };
+            // `mul * left - right <= -off`
+            let mul = linear.mul().unwrap_or(1) as f64;
+            let off = linear.off().unwrap_or(0) as f64;
+            let ab = (mul * left.0 - right.0, mul * left.1 - right.1);
+            let below = edge(ab, -off, true, false);
+            match linear.cmp() {
+                Compare::LTE => vec![below],
+                Compare::LT => vec![Edge {
+                    inclusive: false,
+                    ..below
+                }],
+                Compare::GTE => vec![Edge {
+                    plane: below.plane.opposite(),
+                    ..below
+                }],
+                Compare::GT => vec![Edge {
+                    plane: below.plane.opposite(),
+                    inclusive: false,
+                    ..below
+                }],
+                Compare::EQ => vec![Edge {
+                    line_only: true,
+                    ..below
+                }],
+            }
+        }
+    }
+}
+
+/// limit of a single constraint that can be dragged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+    Bot,
+    Top,
+    Fixed,
+}
+
+/// a dragged limit at the integer under the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grab {
+    pub cid: ConstraintId,
+    pub handle: Handle,
+    /// the line is vertical, the variable is on the x axis
+    pub vertical: bool,
+    pub value: i64,
+}
+
+/// limits of a constraint on `x` or `y` that can be dragged, at their current value
+fn handles(cid: ConstraintId, constraint: &Constraint, x: usize, y: usize) -> Vec<Grab> {
+    let Constraint::Single(single) = constraint else {
+        return Vec::new();
+    };
+    let vertical = match single.id() {
+        id if id == x => true,
+        id if id == y => false,
+        _ => return Vec::new(),
+    };
+    let grab = |handle, value| Grab {
+        cid,
+        handle,
+        vertical,
+        value,
+    };
+    match single {
+        SingleConstrain::Boundary(boundary) => {
+            let bot = boundary.bot.map(|b| grab(Handle::Bot, b.limit.point));
+            let top = boundary.top.map(|t| grab(Handle::Top, t.limit.point));
+            bot.into_iter().chain(top).collect()
+        }
+        SingleConstrain::Fixed(fixed) => vec![grab(Handle::Fixed, fixed.value)],
+    }
+}
+
+/// `constraint` with the limit of `handle` moved to `value`, the inclusiveness is kept
+fn moved(constraint: &Constraint, handle: Handle, value: i64) -> Result<Constraint, String> {
+    match (*constraint, handle) {
+        (Constraint::Single(SingleConstrain::Boundary(mut boundary)), Handle::Bot) => {
+            let equal = boundary.bot.is_none_or(|b| b.limit.equal);
+            boundary
+                .set_bot(Limit {
+                    point: value,
+                    equal,
+                })
+                .map_err(|e| e.to_string())?;
+            Ok(Constraint::Single(SingleConstrain::Boundary(boundary)))
+        }
+        (Constraint::Single(SingleConstrain::Boundary(mut boundary)), Handle::Top) => {
+            let equal = boundary.top.is_some_and(|t| t.limit.equal);
+            boundary
+                .set_top(Limit {
+                    point: value,
+                    equal,
+                })
+                .map_err(|e| e.to_string())?;
+            Ok(Constraint::Single(SingleConstrain::Boundary(boundary)))
+        }
+        (Constraint::Single(SingleConstrain::Fixed(fixed)), Handle::Fixed) => Ok(
+            Constraint::Single(SingleConstrain::Fixed(Fixed::new(fixed.id, value))),
+        ),
+        _ => Err("the constraint changed while it was dragged".to_string()),
+    }
+}
+
+/// `x`, or `#id` for unnamed variables
+fn name(model: &Model, id: usize) -> String {
+    match model.name(id) {
+        Some(name) => name.to_string(),
+        None => format!("#{}", id),
+    }
+}
+
+/// corners of a box, counterclockwise
+fn corners(bounds: &PlotBounds) -> Vec<[f64; 2]> {
+    let ([x0, y0], [x1, y1]) = (bounds.min(), bounds.max());
+    vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]]
+}
+
+/// range of the axis showing a domain, with a margin
+fn range(domain: Option<Domain>) -> (f64, f64) {
+    let domain = domain.unwrap_or_default();
+    let (lo, hi) = match (domain.lo, domain.hi) {
+        (Some(lo), Some(hi)) => (lo as f64, hi as f64),
+        (Some(lo), None) => (lo as f64, lo as f64 + 20.0),
+        (None, Some(hi)) => (hi as f64 - 20.0, hi as f64),
+        (None, None) => (-10.0, 10.0),
+    };
+    let margin = ((hi - lo) * 0.2).max(1.0);
+    (lo - margin, hi + margin)
+}
+
+/// plot of the feasible region of `x` and `y`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionPlot {
+    pub x: usize,
+    pub y: usize,
+    /// limit under the pointer in the last frame, the plot is not panned from it
+    hovered: Option<Grab>,
+    grab: Option<Grab>,
+    /// why the last dragged limit was not stored
+    error: Option<String>,
+    /// point placed by a click, checked against the model
+    probe: Option<[i64; 2]>,
+}
+
+impl RegionPlot {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self {
+            x,
+            y,
+            hovered: None,
+            grab: None,
+            error: None,
+            probe: None,
+        }
+    }
+
+    /// choose the variables of both axes among the ones of the model
+    pub fn axes_ui(&mut self, ui: &mut egui::Ui, model: &Model) {
+        let name = |id| name(model, id);
+        let mut ids = model.variables();
+        ids.extend([self.x, self.y]);
+
+        ui.horizontal(|ui| {
+            for (label, axis) in [("x", &mut self.x), ("y", &mut self.y)] {
+                egui::ComboBox::from_label(label)
+                    .selected_text(name(*axis))
+                    .show_ui(ui, |ui| {
+                        for id in ids.iter() {
+                            ui.selectable_value(axis, *id, name(*id));
+                        }
+                    });
+            }
+        });
+    }
+
+    /// the view when the plot is reset, around the propagated bounds of both variables
+    fn view(&self, model: &Model) -> PlotBounds {
+        let domains = model.propagated(&HashMap::new()).unwrap_or_default();
+        let (x0, x1) = range(domains.get(&self.x).copied());
+        let (y0, y1) = range(domains.get(&self.y).copied());
+        PlotBounds::from_min_max([x0, y0], [x1, y1])
+    }
+
+    /// store the dragged limit, boundaries are checked by `set_bot` and `set_top` again
+    fn release(&mut self, model: &mut Model, grab: Grab) {
+        let limit = |equal| Limit {
+            point: grab.value,
+            equal,
+        };
+        let stored = match (model.get(grab.cid), grab.handle) {
+            (Some(Constraint::Single(SingleConstrain::Boundary(b))), Handle::Bot) => model
+                .set_bot(grab.cid, limit(b.bot.is_none_or(|b| b.limit.equal)))
+                .map_err(|e| e.to_string()),
+            (Some(Constraint::Single(SingleConstrain::Boundary(b))), Handle::Top) => model
+                .set_top(grab.cid, limit(b.top.is_some_and(|t| t.limit.equal)))
+                .map_err(|e| e.to_string()),
+            (Some(c), Handle::Fixed) => moved(&c, Handle::Fixed, grab.value).map(|c| {
+                model.replace(grab.cid, c);
+            }),
+            _ => Err(format!("{} was removed while it was dragged", grab.cid)),
+        };
+        self.error = stored.err();
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, model: &mut Model) -> Response {
+        let view = self.view(model);
+        let mut constraints: Vec<(ConstraintId, Constraint, Vec<Edge>)> = model
+            .constraints()
+            .into_iter()
+            .map(|(cid, c)| (cid, c, edges(&c, self.x, self.y)))
+            .filter(|(_, _, edges)| !edges.is_empty())
+            .collect();
+
+        // the dragged limit is drawn where it would be stored
+        let mut dragged = None;
+        if let Some(grab) = self.grab {
+            if let Some((_, constraint, edges)) =
+                constraints.iter_mut().find(|(cid, _, _)| *cid == grab.cid)
+            {
+                dragged = Some(moved(constraint, grab.handle, grab.value));
+                if let Some(Ok(c)) = dragged.clone() {
+                    *constraint = c;
+                    *edges = self::edges(&c, self.x, self.y);
+                }
+            }
+        }
+        let grabs: Vec<Grab> = constraints
+            .iter()
+            .flat_map(|(cid, c, _)| handles(*cid, c, self.x, self.y))
+            .collect();
+
+        let probe = self.probe.map(|p| self.check(model, p)).unwrap_or_default();
+
+        let (x, y) = (self.x, self.y);
+        let response = Plot::new(("region", x, y))
+            .legend(Legend::default())
+            .x_axis_label(model.name(x).unwrap_or("x"))
+            .y_axis_label(model.name(y).unwrap_or("y"))
+            // the region is clipped to the view, so it may not grow the view
+            .auto_bounds(false.into())
+            .include_x(view.min()[0])
+            .include_x(view.max()[0])
+            .include_y(view.min()[1])
+            .include_y(view.max()[1])
+            // dragging a limit does not pan the plot
+            .allow_drag(self.hovered.is_none() && self.grab.is_none())
+            .show(ui, |plot_ui| {
+                // the bounds of the last frame, widened to hide the lag while dragging
+                let mut clip = plot_ui.plot_bounds();
+                let (width, height) = (clip.width(), clip.height());
+                clip.expand_x(width);
+                clip.expand_y(height);
+                clip.merge(&view);
+                let clip = corners(&clip);
+
+                let mut region = clip.clone();
+                for (cid, _, edges) in constraints.iter() {
+                    if model.is_soft(*cid) {
+                        continue;
+                    }
+                    for edge in edges {
+                        region = edge.plane.clip(&region);
+                        if edge.line_only {
+                            region = edge.plane.opposite().clip(&region);
+                        }
+                    }
+                }
+                if !region.is_empty() {
+                    plot_ui.polygon(
+                        Polygon::new(PlotPoints::from(region))
+                            .name("feasible region")
+                            .fill_color(Color32::from_rgba_unmultiplied(100, 150, 220, 60))
+                            .stroke((1.0, Color32::from_rgb(100, 150, 220))),
+                    );
+                }
+
+                for (cid, constraint, edges) in constraints.iter() {
+                    let name = format!("{}: {}", cid, model.describe(constraint));
+                    for edge in edges {
+                        let Some(segment) = edge.plane.segment(&clip) else {
+                            continue;
+                        };
+                        plot_ui.line(
+                            Line::new(PlotPoints::from(segment.to_vec()))
+                                .style(match edge.inclusive && !edge.line_only {
+                                    true => LineStyle::Dotted { spacing: 10.0 },
+                                    false => LineStyle::Solid,
+                                })
+                                .name(&name)
+                                .width(match model.is_soft(*cid) {
+                                    true => 1.5,
+                                    false => 3.0,
+                                }),
+                        );
+                    }
+                }
+
+                let pointer = plot_ui.pointer_coordinate();
+                // closest limit within reach of the pointer
+                self.hovered = pointer.and_then(|pointer| {
+                    let at = plot_ui.screen_from_plot(pointer);
+                    grabs
+                        .iter()
+                        .map(|grab| {
+                            let distance = match grab.vertical {
+                                true => {
+                                    let line = PlotPoint::new(grab.value as f64, pointer.y);
+                                    (plot_ui.screen_from_plot(line).x - at.x).abs()
+                                }
+                                false => {
+                                    let line = PlotPoint::new(pointer.x, grab.value as f64);
+                                    (plot_ui.screen_from_plot(line).y - at.y).abs()
+                                }
+                            };
+                            (distance, *grab)
+                        })
+                        .filter(|(distance, _)| *distance <= GRAB_DISTANCE)
+                        .min_by(|a, b| a.0.total_cmp(&b.0))
+                        .map(|(_, grab)| grab)
+                });
+
+                let response = plot_ui.response().clone();
+                if let (true, None, Some(pointer)) = (response.clicked(), self.hovered, pointer) {
+                    self.probe = Some([pointer.x.round() as i64, pointer.y.round() as i64]);
+                }
+                if let Some([px, py]) = self.probe {
+                    plot_ui.points(
+                        Points::new(vec![[px as f64, py as f64]])
+                            .name("probe")
+                            .shape(MarkerShape::Circle)
+                            .radius(6.0)
+                            .color(match probe.violations.is_empty() {
+                                true => Color32::from_rgb(60, 180, 75),
+                                false => Color32::from_rgb(220, 50, 50),
+                            }),
+                    );
+                }
+
+                if response.drag_started() && self.grab.is_none() {
+                    self.grab = self.hovered;
+                    // keep the view still while the limits it was fitted to move
+                    plot_ui.set_auto_bounds(Vec2b::FALSE);
+                }
+                let grab = self.grab.as_mut()?;
+                if !response.dragged() {
+                    return Some(*grab);
+                }
+                if let Some(pointer) = pointer {
+                    let value = match grab.vertical {
+                        true => pointer.x,
+                        false => pointer.y,
+                    };
+                    grab.value = value.round() as i64;
+                    let color = match dragged {
+                        Some(Err(_)) => Color32::RED,
+                        _ => plot_ui.ctx().style().visuals.text_color(),
+                    };
+                    plot_ui.text(
+                        Text::new(pointer, format!("  {}", grab.value))
+                            .anchor(Align2::LEFT_BOTTOM)
+                            .color(color),
+                    );
+                }
+                None
+            });
+
+        if let Some(grab) = self.grab.or(self.hovered) {
+            ui.ctx().set_cursor_icon(match grab.vertical {
+                true => CursorIcon::ResizeHorizontal,
+                false => CursorIcon::ResizeVertical,
+            });
+        }
+        if let Some(grab) = response.inner {
+            self.grab = None;
+            self.release(model, grab);
+        }
+        match (self.grab.is_some(), dragged) {
+            (true, Some(Err(e))) => {
+                ui.colored_label(Color32::RED, e);
+            }
+            _ => {
+                if let Some(e) = &self.error {
+                    ui.colored_label(Color32::RED, e);
+                }
+            }
+        }
+        if let Some([px, py]) = self.probe {
+            ui.horizontal(|ui| {
+                let at = match self.x == self.y {
+                    true => format!("{} = {}", name(model, self.x), px),
+                    false => format!(
+                        "{} = {}, {} = {}",
+                        name(model, self.x),
+                        px,
+                        name(model, self.y),
+                        py
+                    ),
+                };
+                match probe.violations.is_empty() {
+                    true => ui.colored_label(Color32::from_rgb(60, 180, 75), at + " holds"),
+                    false => ui.colored_label(Color32::RED, at + " violates"),
+                };
+                if !probe.soft.is_empty() {
+                    ui.weak(format!("penalty {}", probe.penalty()));
+                }
+                if ui.small_button("clear").clicked() {
+                    self.probe = None;
+                }
+            });
+            for v in probe.violations.iter() {
+                ui.label(format!("  {}", probe.line(v)));
+            }
+            for v in probe.soft.iter() {
+                ui.weak(format!("  soft {}", probe.line(v)));
+            }
+        }
+        response.response
+    }
+
+    /// constraints on the axes checked at `probe`, constraints of other variables miss a value
+    /// and are not evaluated
+    fn check(&self, model: &Model, [px, py]: [i64; 2]) -> CheckReport {
+        // a view of one variable checks a value, not a pair
+        let probe = match self.x == self.y {
+            true => Assignment::from([(self.x, px)]),
+            false => Assignment::from([(self.x, px), (self.y, py)]),
+        };
+        model.check(&probe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::model::Soft;
+
+    fn limit(point: i64, equal: bool) -> Option<Limit<i64>> {
+        Some(Limit { point, equal })
+    }
+
+    #[test]
+    fn half_planes_clip_and_cut_the_view() {
+        let square = vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        // x + y <= 10
+        let diagonal = HalfPlane {
+            a: 1.0,
+            b: 1.0,
+            c: 10.0,
+        };
+        assert_eq!(
+            diagonal.clip(&square),
+            vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]]
+        );
+        assert_eq!(
+            diagonal.opposite().clip(&square),
+            vec![[10.0, 0.0], [10.0, 10.0], [0.0, 10.0]]
+        );
+        // x <= 4
+        let vertical = HalfPlane {
+            a: 1.0,
+            b: 0.0,
+            c: 4.0,
+        };
+        assert_eq!(vertical.segment(&square), Some([[4.0, 0.0], [4.0, 10.0]]));
+        let outside = HalfPlane {
+            c: 20.0,
+            ..vertical
+        };
+        assert_eq!(outside.segment(&square), None);
+    }
+
+    #[test]
+    fn edges_of_every_kind() {
+        let mut model = Model::new();
+        let boundary = model
+            .add_boundary(0, limit(5, false), limit(0, true))
+            .unwrap();
+        let fixed = model.add_fixed(1, 3);
+        // 2 * x + 1 > y
+        let relation = model.add_linear(0, 1, Some(2), Some(1), Compare::GT);
+        let other = model.add_linear(1, 2, None, None, Compare::LT);
+        let edges = |cid| edges(&model.get(cid).unwrap(), 0, 1);
+
+        let plane = |a, b, c| HalfPlane { a, b, c };
+        assert_eq!(
+            edges(boundary),
+            vec![
+                Edge {
+                    plane: plane(-1.0, 0.0, 0.0),
+                    inclusive: true,
+                    line_only: false,
+                },
+                Edge {
+                    plane: plane(1.0, 0.0, 5.0),
+                    inclusive: false,
+                    line_only: false,
+                },
+            ]
+        );
+        assert_eq!(
+            edges(fixed),
+            vec![Edge {
+                plane: plane(0.0, 1.0, 3.0),
+                inclusive: true,
+                line_only: true,
+            }]
+        );
+        // -2 * x + y <= 1
+        assert_eq!(
+            edges(relation),
+            vec![Edge {
+                plane: plane(-2.0, 1.0, 1.0),
+                inclusive: false,
+                line_only: false,
+            }]
+        );
+        assert!(edges(other).is_empty());
+
+        // with the axes swapped the relation is a line through the other plane
+        let swapped = self::edges(&model.get(relation).unwrap(), 1, 0);
+        assert_eq!(swapped[0].plane, plane(1.0, -2.0, 1.0));
+    }
+
+    #[test]
+    fn dragged_limits_keep_their_inclusiveness() {
+        let mut model = Model::new();
+        let boundary = model
+            .add_boundary(0, limit(5, false), limit(0, true))
+            .unwrap();
+        let fixed = model.add_fixed(1, 3);
+        let c = model.get(boundary).unwrap();
+
+        let values: Vec<_> = handles(boundary, &c, 0, 1)
+            .iter()
+            .map(|g| (g.handle, g.vertical, g.value))
+            .collect();
+        assert_eq!(values, vec![(Handle::Bot, true, 0), (Handle::Top, true, 5)]);
+        let grabs = handles(fixed, &model.get(fixed).unwrap(), 0, 1);
+        assert_eq!((grabs[0].handle, grabs[0].vertical), (Handle::Fixed, false));
+        assert!(handles(boundary, &c, 1, 2).is_empty());
+
+        let Constraint::Single(SingleConstrain::Boundary(b)) = moved(&c, Handle::Top, 8).unwrap()
+        else {
+            panic!("a boundary stays a boundary");
+        };
+        assert_eq!(b.top.unwrap().limit, limit(8, false).unwrap());
+        assert_eq!(b.bot.unwrap().limit, limit(0, true).unwrap());
+        assert!(moved(&c, Handle::Bot, 6).is_err());
+        assert!(moved(&c, Handle::Fixed, 6).is_err());
+
+        let mut plot = RegionPlot::new(0, 1);
+        let mut grab = handles(boundary, &c, 0, 1)[1];
+        grab.value = 9;
+        plot.release(&mut model, grab);
+        assert_eq!(plot.error, None);
+        assert_eq!(
+            model.get(boundary),
+            Some(moved(&c, Handle::Top, 9).unwrap())
+        );
+
+        // the limits may not cross, the model is left as it was
+        grab.handle = Handle::Bot;
+        grab.value = 12;
+        plot.release(&mut model, grab);
+        assert!(plot.error.is_some());
+        assert_eq!(
+            model.get(boundary),
+            Some(moved(&c, Handle::Top, 9).unwrap())
+        );
+
+        model.remove(fixed);
+        plot.release(
+            &mut model,
+            Grab {
+                cid: fixed,
+                handle: Handle::Fixed,
+                vertical: false,
+                value: 4,
+            },
+        );
+        assert_eq!(
+            plot.error,
+            Some(format!("{} was removed while it was dragged", fixed))
+        );
+    }
+
+    #[test]
+    fn probes_are_judged_by_hard_constraints() {
+        let mut model = Model::new();
+        // declared with y on the left, the view has x on the horizontal axis
+        model.add_linear(0, 1, None, None, Compare::LT);
+        let soft = model.add_fixed(1, 3);
+        model.set_soft(soft, Soft::new(2, 0));
+        // other variables are left out
+        model.add_linear(1, 2, None, None, Compare::GT);
+        let plot = RegionPlot::new(1, 0);
+
+        let probe = plot.check(&model, [5, 1]);
+        assert!(probe.violations.is_empty());
+        assert_eq!(probe.soft.len(), 1);
+        assert_eq!(probe.penalty(), 4);
+
+        let probe = plot.check(&model, [1, 5]);
+        assert_eq!(probe.violations.len(), 1);
+        assert_eq!(probe.checked, 2);
+
+        // a view of a single variable checks only its own constraints
+        let probe = RegionPlot::new(1, 1).check(&model, [3, 100]);
+        assert!(probe.violations.is_empty() && probe.soft.is_empty());
+        assert_eq!(probe.checked, 1);
+    }
+}